@@ -0,0 +1,55 @@
+use aggregator_methods::PAYMENT_INSTRUCTION_ID;
+use method::PaymentInstructionOutput;
+use risc0_zkvm::guest::env;
+use risc0_zkvm::serde::to_vec;
+use sha2::{Digest, Sha256};
+
+/// Recursively aggregates a batch of already-proven payment instruction
+/// receipts into a single succinct output: every inner receipt is verified
+/// as an assumption of this proof (RISC Zero composition), so the resulting
+/// receipt attests to the entire batch without a verifier having to check
+/// each member proof individually.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct AggregatedBatchOutput {
+    pub batch_size: u32,
+    /// SHA-256 over each aggregated `PaymentInstructionOutput`'s `root`,
+    /// `debtor_hash` and `creditor_hash`, in batch order. The outputs' other
+    /// fields (amount bounds, currency hash, expiry, hash suite) are not
+    /// part of this hash.
+    pub combined_commitment: [u8; 32],
+    pub total_amount_milli: u64,
+}
+
+fn main() {
+    let outputs: Vec<PaymentInstructionOutput> = env::read();
+
+    let mut hasher = Sha256::new();
+    let mut total_amount_milli: u64 = 0;
+
+    for output in &outputs {
+        // Verify that this output really was committed by the payment
+        // instruction guest program. This is what makes the aggregate
+        // proof stand in for N individual proofs.
+        let journal_words = to_vec(output).expect("journal encoding must not fail");
+        let journal_bytes: &[u8] = bytemuck::cast_slice(&journal_words);
+        env::verify(PAYMENT_INSTRUCTION_ID, journal_bytes)
+            .expect("inner payment instruction receipt must verify");
+
+        hasher.update(output.root);
+        hasher.update(output.debtor_hash);
+        hasher.update(output.creditor_hash);
+        total_amount_milli = total_amount_milli
+            .checked_add(output.max_amount_milli)
+            .expect("aggregate amount overflow");
+    }
+
+    let digest = hasher.finalize();
+    let mut combined_commitment = [0u8; 32];
+    combined_commitment.copy_from_slice(&digest);
+
+    env::commit(&AggregatedBatchOutput {
+        batch_size: outputs.len() as u32,
+        combined_commitment,
+        total_amount_milli,
+    });
+}
@@ -3,6 +3,7 @@ use test_utils::{
     mock_data::MockData,
     payment_instruction_generator::PaymentInstructionGenerator,
     test_helpers::{create_test_config, generate_proof, TestScenario},
+    ProofMode, TestConfig,
 };
 
 fn bench_proof_generation_simple(c: &mut Criterion) {
@@ -60,10 +61,36 @@ fn bench_proof_generation_edge_cases(c: &mut Criterion) {
     group.finish();
 }
 
+/// Compares proving time against resulting proof size across the three
+/// selectable receipt kinds, the same tradeoff Solana/Substrate benches
+/// draw out between serialization cost and execution cost.
+fn bench_proof_generation_by_mode(c: &mut Criterion) {
+    let input = MockData::simple_valid_input();
+
+    let mut group = c.benchmark_group("proof_generation_by_mode");
+
+    for mode in [ProofMode::Composite, ProofMode::Succinct, ProofMode::Groth16] {
+        let config = TestConfig {
+            proof_mode: mode,
+            ..create_test_config(TestScenario::Fast)
+        };
+
+        group.bench_with_input(BenchmarkId::new("mode", format!("{:?}", mode)), &mode, |b, _| {
+            b.iter(|| {
+                let result = generate_proof(black_box(&input), black_box(&config));
+                black_box(result)
+            })
+        });
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_proof_generation_simple,
     bench_proof_generation_batch,
-    bench_proof_generation_edge_cases
+    bench_proof_generation_edge_cases,
+    bench_proof_generation_by_mode
 );
 criterion_main!(benches);
@@ -1,16 +1,25 @@
 use std::env;
 use std::time::Instant;
 use test_utils::{
+    execution_trace::trace_verify_payment_instruction,
     payment_instruction_generator::PaymentInstructionGenerator,
+    proof_aggregation::generate_aggregate_proof,
     proof_validator::ProofValidator,
+    prover_backend::{BackendKind, ProverBackendFactory},
+    receipt_export::{ReceiptBundle, VerifierExport},
     test_helpers::{
-        create_test_config, generate_and_verify_proof, load_input_from_file,
-        save_input_to_temp_file, TestScenario,
+        create_test_config_with_backend, generate_proof, load_input_from_file,
+        save_input_to_temp_file, verify_receipt, TestScenario,
     },
 };
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    // The `--backend <local|remote|mock>` flag can appear anywhere after the
+    // subcommand and is stripped out before positional argument parsing, so
+    // every command dispatches through the same selected `ProverBackend`.
+    let (backend, args) = extract_backend_flag(env::args().collect());
+    let (aggregate, args) = extract_flag(args, "--aggregate");
+    let (out_file, args) = extract_value_flag(args, "--out");
 
     if args.len() < 2 {
         print_usage();
@@ -18,19 +27,23 @@ fn main() {
     }
 
     match args[1].as_str() {
-        "basic" => run_basic_test(),
-        "samples" => run_sample_tests(),
-        "stress" => run_stress_test(),
+        "basic" => run_basic_test(backend, out_file),
+        "samples" => run_sample_tests(backend),
+        "stress" => run_stress_test(backend),
         "batch" => {
             let count = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(3);
-            run_batch_test(count);
+            if aggregate {
+                run_batch_aggregate_test(count, backend);
+            } else {
+                run_batch_test(count, backend, out_file);
+            }
         }
         "file" => {
             if args.len() < 3 {
                 println!("Usage: cargo run --example phase2_cli file <input.json>");
                 return;
             }
-            run_file_test(&args[2]);
+            run_file_test(&args[2], backend, out_file);
         }
         "generate" => {
             let default_file = "generated_input.json".to_string();
@@ -44,6 +57,14 @@ fn main() {
             }
             validate_input_file(&args[2]);
         }
+        "trace" => {
+            if args.len() < 3 {
+                println!("Usage: cargo run --example phase2_cli trace <input.json>");
+                return;
+            }
+            trace_input_file(&args[2], out_file);
+        }
+        "export-verifier" => export_verifier(out_file),
         _ => {
             println!("Unknown command: {}", args[1]);
             print_usage();
@@ -51,6 +72,75 @@ fn main() {
     }
 }
 
+/// Pull a `--backend <kind>` pair out of `args`, defaulting to the local
+/// prover if absent, and return the remaining positional arguments.
+fn extract_backend_flag(mut args: Vec<String>) -> (BackendKind, Vec<String>) {
+    let mut backend = BackendKind::Local;
+
+    if let Some(flag_index) = args.iter().position(|arg| arg == "--backend") {
+        if let Some(value) = args.get(flag_index + 1) {
+            backend = value.parse().unwrap_or_else(|error| {
+                println!("⚠️  {}, defaulting to local", error);
+                BackendKind::Local
+            });
+        }
+        args.drain(flag_index..(flag_index + 2).min(args.len()));
+    }
+
+    (backend, args)
+}
+
+/// Pull a bare boolean flag (e.g. `--aggregate`) out of `args` and return
+/// whether it was present alongside the remaining positional arguments.
+fn extract_flag(mut args: Vec<String>, flag: &str) -> (bool, Vec<String>) {
+    match args.iter().position(|arg| arg == flag) {
+        Some(flag_index) => {
+            args.remove(flag_index);
+            (true, args)
+        }
+        None => (false, args),
+    }
+}
+
+/// Pull a `<flag> <value>` pair (e.g. `--out <file.json>`) out of `args` and
+/// return the value alongside the remaining positional arguments.
+fn extract_value_flag(mut args: Vec<String>, flag: &str) -> (Option<String>, Vec<String>) {
+    match args.iter().position(|arg| arg == flag) {
+        Some(flag_index) => {
+            let value = args.get(flag_index + 1).cloned();
+            args.drain(flag_index..(flag_index + 2).min(args.len()));
+            (value, args)
+        }
+        None => (None, args),
+    }
+}
+
+/// Prove and verify `input` directly through the local RISC Zero prover
+/// (bypassing the `ProverBackend` abstraction, which has no raw `Receipt` to
+/// hand back), build a `ReceiptBundle`, and write it to `out_path` as JSON.
+fn export_receipt_bundle(
+    input: &test_utils::payment_instruction_generator::PaymentInstructionInput,
+    config: &test_utils::TestConfig,
+    out_path: &str,
+) {
+    print!("💾 Exporting receipt bundle to {}... ", out_path);
+    let result = generate_proof(input, config).and_then(|(receipt, metrics)| {
+        let output = verify_receipt(&receipt)?;
+        ReceiptBundle::new(&receipt, &output, &metrics)
+    });
+
+    match result {
+        Ok(bundle) => match serde_json::to_string_pretty(&bundle) {
+            Ok(json) => match std::fs::write(out_path, json) {
+                Ok(()) => println!("✅ Done"),
+                Err(e) => println!("❌ Failed to write file: {}", e),
+            },
+            Err(e) => println!("❌ Failed to serialize bundle: {}", e),
+        },
+        Err(e) => println!("❌ Failed to build receipt bundle: {}", e),
+    }
+}
+
 fn print_usage() {
     println!("Phase 2 CLI - RISC Zero Proof Generation and Verification");
     println!();
@@ -65,21 +155,37 @@ fn print_usage() {
     println!("  file <input.json>        Generate proof from JSON file");
     println!("  generate [output.json]   Generate sample input file");
     println!("  validate <input.json>    Validate input file without proof");
+    println!("  trace <input.json>       Run every verification check and report pass/fail per check");
+    println!();
+    println!("  export-verifier          Emit the ABI journal layout + image ID for an on-chain verifier");
+    println!();
+    println!("OPTIONS:");
+    println!("  --backend <local|remote|mock>   Prover backend to dispatch through (default: local)");
+    println!("  --aggregate                     For `batch`, fold the batch into one recursive proof");
+    println!("  --out <file.json>               Export a ReceiptBundle (basic/file/batch),");
+    println!("                                   VerifierExport (export-verifier), or");
+    println!("                                   VerificationTrace (trace) to a JSON file");
     println!();
     println!("EXAMPLES:");
     println!("  cargo run --example phase2_cli basic");
+    println!("  cargo run --example phase2_cli basic --out receipt.json");
     println!("  cargo run --example phase2_cli batch 5");
+    println!("  cargo run --example phase2_cli batch 5 --aggregate");
     println!("  cargo run --example phase2_cli generate my_input.json");
     println!("  cargo run --example phase2_cli file my_input.json");
+    println!("  cargo run --example phase2_cli stress --backend mock");
+    println!("  cargo run --example phase2_cli export-verifier --out verifier.json");
+    println!("  cargo run --example phase2_cli trace my_input.json --out trace.json");
 }
 
-fn run_basic_test() {
+fn run_basic_test(backend_kind: BackendKind, out_file: Option<String>) {
     println!("🚀 Phase 2: Basic Proof Generation Test");
     println!("========================================");
 
     let mut generator = PaymentInstructionGenerator::new();
     let input = generator.generate_payment_instruction_input();
-    let config = create_test_config(TestScenario::Fast);
+    let config = create_test_config_with_backend(TestScenario::Fast, backend_kind);
+    let backend = ProverBackendFactory::create(backend_kind);
 
     println!("\n📋 Input Details:");
     println!("  Debtor: {}", extract_name(&input.debtor_data));
@@ -104,7 +210,7 @@ fn run_basic_test() {
     println!("\n⚡ Generating RISC Zero proof...");
     let start_time = Instant::now();
 
-    match generate_and_verify_proof(&input, &config) {
+    match backend.generate_and_verify(&input, &config) {
         Ok((output, metrics)) => {
             let total_time = start_time.elapsed();
 
@@ -128,15 +234,26 @@ fn run_basic_test() {
             println!("To run this test, ensure RISC Zero toolchain is installed.");
         }
     }
+
+    if let Some(out_path) = out_file {
+        if backend_kind != BackendKind::Local {
+            println!(
+                "⚠️  --out requires a real receipt; ignoring --backend {:?} and proving locally",
+                backend_kind
+            );
+        }
+        export_receipt_bundle(&input, &config, &out_path);
+    }
 }
 
-fn run_sample_tests() {
+fn run_sample_tests(backend_kind: BackendKind) {
     println!("🚀 Phase 2: Sample File Tests");
     println!("==============================");
 
     let mut generator = PaymentInstructionGenerator::new();
     let samples = generator.generate_all_samples();
-    let config = create_test_config(TestScenario::Standard);
+    let config = create_test_config_with_backend(TestScenario::Standard, backend_kind);
+    let backend = ProverBackendFactory::create(backend_kind);
 
     println!("\n📋 Testing {} sample formats...", samples.len());
 
@@ -155,7 +272,7 @@ fn run_sample_tests() {
 
         // Generate proof
         print!("   ⚡ Generating proof... ");
-        match generate_and_verify_proof(input, &config) {
+        match backend.generate_and_verify(input, &config) {
             Ok((output, metrics)) => {
                 println!("✅ Success!");
                 println!("     Proof time: {:?}", metrics.proof_generation_time);
@@ -173,22 +290,24 @@ fn run_sample_tests() {
     }
 }
 
-fn run_stress_test() {
+fn run_stress_test(backend_kind: BackendKind) {
     println!("🚀 Phase 2: Performance Stress Test");
     println!("====================================");
 
     let mut generator = PaymentInstructionGenerator::new();
     let input = generator.generate_payment_instruction_input();
-    let config = create_test_config(TestScenario::Stress);
+    let config = create_test_config_with_backend(TestScenario::Stress, backend_kind);
+    let backend = ProverBackendFactory::create(backend_kind);
 
     println!("\n⚠️  Running stress test with strict performance requirements...");
+    println!("   Backend: {:?}", backend_kind);
     println!("   Max proof time: 3 minutes");
     println!("   Max verify time: 500ms");
     println!("   Max memory: 2GB");
 
     let start_time = Instant::now();
 
-    match generate_and_verify_proof(&input, &config) {
+    match backend.generate_and_verify(&input, &config) {
         Ok((output, metrics)) => {
             let total_time = start_time.elapsed();
 
@@ -226,13 +345,14 @@ fn run_stress_test() {
     }
 }
 
-fn run_batch_test(count: usize) {
+fn run_batch_test(count: usize, backend_kind: BackendKind, out_file: Option<String>) {
     println!("🚀 Phase 2: Batch Proof Generation");
     println!("===================================");
 
     let mut generator = PaymentInstructionGenerator::new();
     let batch = generator.generate_batch(count);
-    let config = create_test_config(TestScenario::Fast);
+    let config = create_test_config_with_backend(TestScenario::Fast, backend_kind);
+    let backend = ProverBackendFactory::create(backend_kind);
 
     println!("\n📋 Generating {} proofs in batch...", count);
 
@@ -245,7 +365,7 @@ fn run_batch_test(count: usize) {
     for (i, input) in batch.iter().enumerate() {
         print!("   {}/{}: ", i + 1, count);
 
-        match generate_and_verify_proof(input, &config) {
+        match backend.generate_and_verify(input, &config) {
             Ok((output, metrics)) => {
                 successful_proofs += 1;
                 total_proof_time += metrics.proof_generation_time;
@@ -282,9 +402,77 @@ fn run_batch_test(count: usize) {
         println!("  Average proof time: {:?}", avg_proof_time);
         println!("  Average verify time: {:?}", avg_verify_time);
     }
+
+    if let Some(out_path) = out_file {
+        if backend_kind != BackendKind::Local {
+            println!(
+                "⚠️  --out requires a real receipt; ignoring --backend {:?} and proving locally",
+                backend_kind
+            );
+        }
+        println!("\n💾 Batch export only covers the first instruction in the batch:");
+        export_receipt_bundle(&batch[0], &config, &out_path);
+    }
 }
 
-fn run_file_test(file_path: &str) {
+/// Batch mode that folds every per-instruction proof into one recursive
+/// aggregate receipt instead of reporting each proof independently.
+/// Aggregation composes real RISC Zero receipts, so it always proves
+/// locally regardless of `--backend`.
+fn run_batch_aggregate_test(count: usize, backend_kind: BackendKind) {
+    println!("🚀 Phase 2: Batch Proof Aggregation");
+    println!("====================================");
+
+    if backend_kind != BackendKind::Local {
+        println!(
+            "⚠️  --aggregate composes real receipts; ignoring --backend {:?} and proving locally",
+            backend_kind
+        );
+    }
+
+    let mut generator = PaymentInstructionGenerator::new();
+    let batch = generator.generate_batch(count);
+    let config = create_test_config_with_backend(TestScenario::Fast, BackendKind::Local);
+
+    println!("\n📋 Proving {} instructions, then aggregating...", count);
+    let start_time = Instant::now();
+
+    match generate_aggregate_proof(&batch, &config) {
+        Ok((_receipt, aggregated, metrics)) => {
+            let total_time = start_time.elapsed();
+
+            println!("\n✅ Aggregate proof generated!");
+            println!("\n📊 Aggregation Results:");
+            println!("  Total time (prove + aggregate): {:?}", total_time);
+            println!("  Instructions included: {}", aggregated.batch_size);
+            println!(
+                "  Total amount (milli-units): {}",
+                aggregated.total_amount_milli
+            );
+            println!(
+                "  Summed individual proof size: {} bytes",
+                metrics.summed_individual_proof_size_bytes
+            );
+            println!(
+                "  Aggregate proof size: {} bytes",
+                metrics.aggregate_proof_size_bytes
+            );
+            println!(
+                "  Verification-cost savings: {} bytes",
+                metrics.bytes_saved()
+            );
+            println!(
+                "  Included instruction commitments: {}",
+                metrics.leaf_commitments.len()
+            );
+        }
+        Err(e) => {
+            println!("❌ Batch aggregation failed: {}", e);
+        }
+    }
+}
+
+fn run_file_test(file_path: &str, backend_kind: BackendKind, out_file: Option<String>) {
     println!("🚀 Phase 2: File-based Proof Generation");
     println!("========================================");
 
@@ -321,9 +509,10 @@ fn run_file_test(file_path: &str) {
 
     // Generate proof
     println!("\n⚡ Generating RISC Zero proof...");
-    let config = create_test_config(TestScenario::Standard);
+    let config = create_test_config_with_backend(TestScenario::Standard, backend_kind);
+    let backend = ProverBackendFactory::create(backend_kind);
 
-    match generate_and_verify_proof(&input, &config) {
+    match backend.generate_and_verify(&input, &config) {
         Ok((output, metrics)) => {
             println!("✅ Proof generation successful!");
             println!("\n📊 Performance Metrics:");
@@ -341,6 +530,41 @@ fn run_file_test(file_path: &str) {
             println!("❌ Proof generation failed: {}", e);
         }
     }
+
+    if let Some(out_path) = out_file {
+        if backend_kind != BackendKind::Local {
+            println!(
+                "⚠️  --out requires a real receipt; ignoring --backend {:?} and proving locally",
+                backend_kind
+            );
+        }
+        export_receipt_bundle(&input, &config, &out_path);
+    }
+}
+
+/// Emit the fixed ABI journal layout and image ID an on-chain settlement
+/// contract needs to verify receipts produced by this crate, without
+/// re-deriving the field ordering from `PaymentInstructionOutput` by hand.
+fn export_verifier(out_file: Option<String>) {
+    println!("🚀 Phase 2: Export On-Chain Verifier Parameters");
+    println!("================================================");
+
+    let export = VerifierExport::for_payment_instruction_guest();
+    let json = match serde_json::to_string_pretty(&export) {
+        Ok(json) => json,
+        Err(e) => {
+            println!("❌ Failed to serialize verifier export: {}", e);
+            return;
+        }
+    };
+
+    match out_file {
+        Some(out_path) => match std::fs::write(&out_path, &json) {
+            Ok(()) => println!("✅ Verifier parameters written to: {}", out_path),
+            Err(e) => println!("❌ Failed to write file: {}", e),
+        },
+        None => println!("{}", json),
+    }
 }
 
 fn generate_input_file(output_file: &str) {
@@ -407,22 +631,66 @@ fn validate_input_file(file_path: &str) {
     // Comprehensive validation
     println!("\n🔍 Running validation checks...");
 
-    match ProofValidator::validate_input_consistency(&input) {
-        Ok(()) => {
-            println!("✅ All validation checks passed!");
-            println!("\n📊 Validation Summary:");
-            println!("  ✅ Debtor hash matches data");
-            println!("  ✅ Creditor hash matches data");
-            println!("  ✅ Currency hash matches data");
-            println!("  ✅ Amount within bounds");
-            println!("  ✅ Expiry date format valid");
-            println!("  ✅ Merkle proofs structure valid");
-
-            println!("\nThis input is ready for proof generation!");
+    let trace = trace_verify_payment_instruction(&input);
+    println!("\n📊 Validation Summary:");
+    for step in &trace.steps {
+        let marker = if step.passed { "✅" } else { "❌" };
+        println!("  {} {}", marker, step.check);
+    }
+
+    if trace.passed {
+        println!("\nThis input is ready for proof generation!");
+    } else {
+        println!("\nPlease fix the input before generating proofs.");
+        println!(
+            "Run `cargo run --example phase2_cli trace {}` for per-check detail.",
+            file_path
+        );
+    }
+}
+
+/// Run every verification check against `file_path`'s input and report a
+/// pass/fail result per check (debtor/creditor/currency hash, amount bounds,
+/// expiry, and each of the five Merkle proofs) instead of the first `panic!`
+/// message the guest would surface. Non-proving: this never invokes the
+/// RISC Zero prover.
+fn trace_input_file(file_path: &str, out_file: Option<String>) {
+    println!("🚀 Phase 2: Execution Trace");
+    println!("============================");
+
+    println!("\n📂 Loading input from: {}", file_path);
+    let input = match load_input_from_file(file_path) {
+        Ok(input) => {
+            println!("✅ File loaded successfully");
+            input
         }
         Err(e) => {
-            println!("❌ Validation failed: {}", e);
-            println!("\nPlease fix the input before generating proofs.");
+            println!("❌ Failed to load file: {}", e);
+            return;
+        }
+    };
+
+    let trace = trace_verify_payment_instruction(&input);
+
+    println!("\n🔍 Trace ({} checks):", trace.steps.len());
+    for step in &trace.steps {
+        let marker = if step.passed { "✅" } else { "❌" };
+        println!("  {} {:<22} {}", marker, step.check, step.detail);
+    }
+
+    if trace.passed {
+        println!("\n✅ Overall: verification would succeed");
+    } else {
+        println!("\n❌ Overall: verification would fail");
+    }
+
+    if let Some(out_path) = out_file {
+        match serde_json::to_string_pretty(&trace) {
+            Ok(json) => match std::fs::write(&out_path, json) {
+                Ok(()) => println!("\n💾 Trace written to {}", out_path),
+                Err(e) => println!("\n❌ Failed to write trace to {}: {}", out_path, e),
+            },
+            Err(e) => println!("\n❌ Failed to serialize trace: {}", e),
         }
     }
 }
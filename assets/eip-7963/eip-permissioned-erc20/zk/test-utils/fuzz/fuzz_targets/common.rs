@@ -0,0 +1,80 @@
+use arbitrary::Arbitrary;
+use test_utils::crypto_utils::HashSuite;
+use test_utils::payment_instruction_generator::PaymentInstructionInput;
+
+/// Structured-but-adversarial input for the payment instruction fuzz targets.
+///
+/// Mirrors the fields of `PaymentInstructionInput` but keeps the Merkle proof
+/// vectors short and the strings unconstrained so `arbitrary` can still reach
+/// interesting shapes (empty proofs, malformed currency codes, boundary
+/// amounts) without spending its whole budget on enormous byte vectors.
+#[derive(Arbitrary, Debug, Clone)]
+pub struct PaymentInstructionFuzzInput {
+    pub root: [u8; 32],
+    pub debtor_hash: [u8; 32],
+    pub creditor_hash: [u8; 32],
+    pub min_amount_milli: u64,
+    pub max_amount_milli: u64,
+    pub currency_hash: [u8; 32],
+    pub expiry: u64,
+    pub debtor_data: String,
+    pub creditor_data: String,
+    pub amount_value: u64,
+    pub currency: String,
+    pub execution_date: String,
+    pub debtor_proof_siblings: Vec<[u8; 32]>,
+    pub debtor_proof_directions: Vec<u8>,
+    pub creditor_proof_siblings: Vec<[u8; 32]>,
+    pub creditor_proof_directions: Vec<u8>,
+    pub amount_proof_siblings: Vec<[u8; 32]>,
+    pub amount_proof_directions: Vec<u8>,
+    pub currency_proof_siblings: Vec<[u8; 32]>,
+    pub currency_proof_directions: Vec<u8>,
+    pub expiry_proof_siblings: Vec<[u8; 32]>,
+    pub expiry_proof_directions: Vec<u8>,
+    pub hash_suite: HashSuite,
+}
+
+impl From<PaymentInstructionFuzzInput> for PaymentInstructionInput {
+    fn from(fuzz: PaymentInstructionFuzzInput) -> Self {
+        PaymentInstructionInput {
+            root: fuzz.root,
+            debtor_hash: fuzz.debtor_hash,
+            creditor_hash: fuzz.creditor_hash,
+            min_amount_milli: fuzz.min_amount_milli,
+            max_amount_milli: fuzz.max_amount_milli,
+            currency_hash: fuzz.currency_hash,
+            expiry: fuzz.expiry,
+            debtor_data: fuzz.debtor_data,
+            creditor_data: fuzz.creditor_data,
+            amount_value: fuzz.amount_value,
+            currency: fuzz.currency,
+            execution_date: fuzz.execution_date,
+            debtor_proof_siblings: fuzz.debtor_proof_siblings,
+            debtor_proof_directions: fuzz.debtor_proof_directions,
+            creditor_proof_siblings: fuzz.creditor_proof_siblings,
+            creditor_proof_directions: fuzz.creditor_proof_directions,
+            amount_proof_siblings: fuzz.amount_proof_siblings,
+            amount_proof_directions: fuzz.amount_proof_directions,
+            currency_proof_siblings: fuzz.currency_proof_siblings,
+            currency_proof_directions: fuzz.currency_proof_directions,
+            expiry_proof_siblings: fuzz.expiry_proof_siblings,
+            expiry_proof_directions: fuzz.expiry_proof_directions,
+            hash_suite: fuzz.hash_suite,
+        }
+    }
+}
+
+/// Seed corpus drawn from the generator's curated samples and edge cases,
+/// used to prime each fuzz target's `corpus/` directory.
+#[allow(dead_code)]
+pub fn seed_inputs() -> Vec<PaymentInstructionInput> {
+    let mut generator = test_utils::payment_instruction_generator::PaymentInstructionGenerator::new();
+    let mut inputs: Vec<PaymentInstructionInput> = generator
+        .generate_all_samples()
+        .into_iter()
+        .map(|(_, input)| input)
+        .collect();
+    inputs.extend(generator.generate_edge_cases());
+    inputs
+}
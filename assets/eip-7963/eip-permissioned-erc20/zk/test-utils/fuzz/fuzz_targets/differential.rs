@@ -0,0 +1,37 @@
+#![no_main]
+
+#[path = "common.rs"]
+mod common;
+
+use common::PaymentInstructionFuzzInput;
+use libfuzzer_sys::fuzz_target;
+use test_utils::guest_logic::verify_payment_instruction;
+use test_utils::payment_instruction_generator::PaymentInstructionInput;
+use test_utils::proof_validator::ProofValidator;
+
+// Differential target: feeds the same bytes to the host-side validator and
+// to `guest_logic::verify_payment_instruction` (which duplicates the guest's
+// acceptance logic for testing) and asserts the crate's core invariant:
+// `validate_input_consistency` returning `Ok` must be exactly the set of
+// inputs the guest accepts, and `validate_output_consistency` must hold for
+// every guest-accepted output.
+fuzz_target!(|fuzz_input: PaymentInstructionFuzzInput| {
+    let input: PaymentInstructionInput = fuzz_input.into();
+
+    let host_accepts = ProofValidator::validate_input_consistency(&input).is_ok();
+    let guest_result = verify_payment_instruction(&input);
+
+    match (host_accepts, &guest_result) {
+        (true, Err(e)) => panic!(
+            "host validator accepted an input the guest rejected: {}",
+            e
+        ),
+        (false, Ok(_)) => panic!("guest accepted an input the host validator rejected"),
+        _ => {}
+    }
+
+    if let Ok(output) = guest_result {
+        ProofValidator::validate_output_consistency(&input, &output)
+            .expect("guest-accepted output must satisfy validate_output_consistency");
+    }
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+#[path = "common.rs"]
+mod common;
+
+use common::PaymentInstructionFuzzInput;
+use libfuzzer_sys::fuzz_target;
+use test_utils::payment_instruction_generator::PaymentInstructionInput;
+use test_utils::test_helpers::{create_test_config, generate_and_verify_proof, TestScenario};
+
+// Slow target: runs the full `generate_and_verify_proof` pipeline under a
+// `TestScenario::Fast` config so the RISC Zero executor actually traces the
+// guest program for every input libFuzzer mutates into. Catches panics and
+// divergences that only manifest once the guest is running, not just during
+// host-side validation.
+fuzz_target!(|fuzz_input: PaymentInstructionFuzzInput| {
+    let input: PaymentInstructionInput = fuzz_input.into();
+    let config = create_test_config(TestScenario::Fast);
+    let _ = generate_and_verify_proof(&input, &config);
+});
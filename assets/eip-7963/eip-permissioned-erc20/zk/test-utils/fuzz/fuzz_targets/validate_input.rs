@@ -0,0 +1,18 @@
+#![no_main]
+
+#[path = "common.rs"]
+mod common;
+
+use common::PaymentInstructionFuzzInput;
+use libfuzzer_sys::fuzz_target;
+use test_utils::payment_instruction_generator::PaymentInstructionInput;
+use test_utils::proof_validator::ProofValidator;
+
+// Fast target: only exercises host-side input validation, no proving. This
+// is the cheapest of the three targets and should be run with the largest
+// iteration budget to shake out panics and hash-domain mismatches in
+// `validate_input_consistency` itself.
+fuzz_target!(|fuzz_input: PaymentInstructionFuzzInput| {
+    let input: PaymentInstructionInput = fuzz_input.into();
+    let _ = ProofValidator::validate_input_consistency(&input);
+});
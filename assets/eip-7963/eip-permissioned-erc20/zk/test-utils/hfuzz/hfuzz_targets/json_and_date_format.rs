@@ -0,0 +1,17 @@
+use honggfuzz::fuzz;
+use test_utils::fuzz::{check_date_format_invariant, check_json_format_invariant};
+
+// Fast target: exercises `validate_json_format` and `validate_date_format`
+// directly on raw strings, rather than through a structured
+// `PaymentInstructionInput`, so honggfuzz can reach malformed JSON and
+// date strings that a structured mutator would rarely produce.
+fn main() {
+    loop {
+        fuzz!(|data: &[u8]| {
+            if let Ok(s) = std::str::from_utf8(data) {
+                check_json_format_invariant(s);
+                check_date_format_invariant(s);
+            }
+        });
+    }
+}
@@ -0,0 +1,17 @@
+use honggfuzz::fuzz;
+use test_utils::fuzz::check_pipeline_invariant;
+use test_utils::payment_instruction_generator::PaymentInstructionInput;
+
+// Runnable via `cargo hfuzz run pipeline_invariant` from this directory.
+// `PaymentInstructionInput` derives `Arbitrary` directly, so honggfuzz
+// mutates its debtor/creditor/amount/currency/expiry fields (and Merkle
+// proof vectors) without going through a separate wrapper struct. Seed the
+// corpus from `test_utils::fuzz::seed_corpus` before running to start from
+// known-interesting inputs rather than pure noise.
+fn main() {
+    loop {
+        fuzz!(|input: PaymentInstructionInput| {
+            check_pipeline_invariant(&input);
+        });
+    }
+}
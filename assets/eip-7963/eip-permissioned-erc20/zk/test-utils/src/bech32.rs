@@ -0,0 +1,152 @@
+//! Minimal bech32 implementation (BIP-173 style checksum and charset), used
+//! to give financial payloads in this crate a compact, error-detecting
+//! human-transmittable encoding the way lightning-invoice encodes BOLT11.
+
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+fn polymod(values: &[u8]) -> u32 {
+    let mut chk: u32 = 1;
+    for &v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ (v as u32);
+        for (i, gen) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= gen;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut v: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    v.push(0);
+    v.extend(hrp.bytes().map(|b| b & 31));
+    v
+}
+
+fn create_checksum(hrp: &str, data: &[u8]) -> [u8; 6] {
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0u8; 6]);
+    let mod_ = polymod(&values) ^ 1;
+    let mut checksum = [0u8; 6];
+    for (i, slot) in checksum.iter_mut().enumerate() {
+        *slot = ((mod_ >> (5 * (5 - i))) & 31) as u8;
+    }
+    checksum
+}
+
+fn verify_checksum(hrp: &str, data: &[u8]) -> bool {
+    let mut values = hrp_expand(hrp);
+    values.extend_from_slice(data);
+    polymod(&values) == 1
+}
+
+/// Encode a human-readable prefix and a sequence of 5-bit groups into a
+/// bech32 string: `prefix + '1' + data + 6-symbol checksum`.
+pub fn encode(hrp: &str, data: &[u8]) -> String {
+    let checksum = create_checksum(hrp, data);
+    let mut result = String::with_capacity(hrp.len() + 1 + data.len() + checksum.len());
+    result.push_str(hrp);
+    result.push('1');
+    for &d in data.iter().chain(checksum.iter()) {
+        result.push(CHARSET[d as usize] as char);
+    }
+    result
+}
+
+/// Decode a bech32 string, verifying its checksum, and return the
+/// human-readable prefix together with the 5-bit data groups (checksum
+/// stripped). Rejects any single-character corruption.
+pub fn decode(input: &str) -> Result<(String, Vec<u8>), String> {
+    if input.len() < 8 {
+        return Err(format!("bech32 string too short: {}", input));
+    }
+
+    let lower = input.to_lowercase();
+    let separator = lower
+        .rfind('1')
+        .ok_or_else(|| format!("missing '1' separator: {}", input))?;
+    let hrp = lower[..separator].to_string();
+    let data_part = &lower[separator + 1..];
+    if hrp.is_empty() || data_part.len() < 6 {
+        return Err(format!("malformed bech32 string: {}", input));
+    }
+
+    let mut data = Vec::with_capacity(data_part.len());
+    for c in data_part.chars() {
+        let value = CHARSET
+            .iter()
+            .position(|&x| x as char == c)
+            .ok_or_else(|| format!("invalid bech32 character '{}': {}", c, input))?;
+        data.push(value as u8);
+    }
+
+    if !verify_checksum(&hrp, &data) {
+        return Err(format!("invalid bech32 checksum: {}", input));
+    }
+
+    let payload = data[..data.len() - 6].to_vec();
+    Ok((hrp, payload))
+}
+
+/// Convert a byte sequence between bit-widths (e.g. 8-bit bytes to 5-bit
+/// groups and back), as required to pack arbitrary payloads into bech32.
+pub fn convert_bits(data: &[u8], from_bits: u32, to_bits: u32, pad: bool) -> Result<Vec<u8>, String> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut ret = Vec::new();
+    let maxv: u32 = (1 << to_bits) - 1;
+
+    for &value in data {
+        if (value as u32) >> from_bits != 0 {
+            return Err(format!("value {} out of range for {} bits", value, from_bits));
+        }
+        acc = (acc << from_bits) | value as u32;
+        bits += from_bits;
+        while bits >= to_bits {
+            bits -= to_bits;
+            ret.push(((acc >> bits) & maxv) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            ret.push(((acc << (to_bits - bits)) & maxv) as u8);
+        }
+    } else if bits >= from_bits || ((acc << (to_bits - bits)) & maxv) != 0 {
+        return Err("invalid padding in bech32 payload".to_string());
+    }
+
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let data = convert_bits(&[0xde, 0xad, 0xbe, 0xef], 8, 5, true).unwrap();
+        let encoded = encode("pi", &data);
+        let (hrp, decoded_data) = decode(&encoded).unwrap();
+        assert_eq!(hrp, "pi");
+        let bytes = convert_bits(&decoded_data, 5, 8, false).unwrap();
+        assert_eq!(bytes, vec![0xde, 0xad, 0xbe, 0xef]);
+    }
+
+    #[test]
+    fn test_single_character_corruption_is_rejected() {
+        let data = convert_bits(&[0x01, 0x02, 0x03], 8, 5, true).unwrap();
+        let encoded = encode("pi", &data);
+
+        let mut corrupted = encoded.into_bytes();
+        let last = corrupted.len() - 1;
+        corrupted[last] = if corrupted[last] == b'q' { b'p' } else { b'q' };
+        let corrupted = String::from_utf8(corrupted).unwrap();
+
+        assert!(decode(&corrupted).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+/// A fitted linear cost model `y = base + slope * x`, mirroring how
+/// Substrate's FRAME benchmarking derives weight formulas from component
+/// ranges swept over a parameter such as Merkle leaf count, JSON payload
+/// size, or batch size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostModel {
+    pub base: f64,
+    pub slope: f64,
+    pub r_squared: f64,
+    pub component_name: String,
+}
+
+impl CostModel {
+    /// Predict the cost for an arbitrary component value using the fitted
+    /// linear formula.
+    pub fn predict(&self, x: f64) -> f64 {
+        self.base + self.slope * x
+    }
+}
+
+/// Fit `y = base + slope * x` via ordinary least squares over `(x, y)`
+/// samples, returning R² as a goodness-of-fit measure.
+///
+/// Requires at least two distinct `x` values and guards against a zero
+/// denominator (constant input), both of which would make the slope
+/// undefined.
+pub fn fit_linear_cost_model(component_name: &str, samples: &[(f64, f64)]) -> Result<CostModel, String> {
+    let distinct_x: std::collections::HashSet<u64> =
+        samples.iter().map(|(x, _)| x.to_bits()).collect();
+    if distinct_x.len() < 2 {
+        return Err(format!(
+            "at least two distinct values of '{}' are required to fit a cost model",
+            component_name
+        ));
+    }
+
+    let n = samples.len() as f64;
+    let sum_x: f64 = samples.iter().map(|(x, _)| x).sum();
+    let sum_y: f64 = samples.iter().map(|(_, y)| y).sum();
+    let sum_xy: f64 = samples.iter().map(|(x, y)| x * y).sum();
+    let sum_x2: f64 = samples.iter().map(|(x, _)| x * x).sum();
+
+    let denominator = n * sum_x2 - sum_x * sum_x;
+    if denominator.abs() < f64::EPSILON {
+        return Err(format!(
+            "'{}' values are constant across samples; cannot fit a slope",
+            component_name
+        ));
+    }
+
+    let slope = (n * sum_xy - sum_x * sum_y) / denominator;
+    let base = (sum_y - slope * sum_x) / n;
+
+    let mean_y = sum_y / n;
+    let ss_tot: f64 = samples.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    let r_squared = if ss_tot.abs() < f64::EPSILON {
+        1.0
+    } else {
+        let ss_res: f64 = samples
+            .iter()
+            .map(|(x, y)| (y - (base + slope * x)).powi(2))
+            .sum();
+        1.0 - ss_res / ss_tot
+    };
+
+    Ok(CostModel {
+        base,
+        slope,
+        r_squared,
+        component_name: component_name.to_string(),
+    })
+}
+
+/// Sweep `component_values`, measuring `measure` for each `repetitions`
+/// times and discarding the first warm-up iteration per point, then fit a
+/// linear cost model over the averaged measurements.
+pub fn benchmark_cost_model<F>(
+    component_name: &str,
+    component_values: &[u64],
+    repetitions: usize,
+    mut measure: F,
+) -> Result<CostModel, String>
+where
+    F: FnMut(u64) -> f64,
+{
+    if repetitions < 2 {
+        return Err(
+            "repetitions must be at least 2 (one warm-up run plus one measured run)".to_string(),
+        );
+    }
+
+    let mut samples = Vec::with_capacity(component_values.len());
+    for &x in component_values {
+        let mut total = 0.0;
+        for i in 0..repetitions {
+            let y = measure(x);
+            if i == 0 {
+                continue; // discard the warm-up iteration
+            }
+            total += y;
+        }
+        samples.push((x as f64, total / (repetitions - 1) as f64));
+    }
+
+    fit_linear_cost_model(component_name, &samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_exact_linear_data() {
+        // y = 10 + 2x
+        let samples = [(1.0, 12.0), (2.0, 14.0), (3.0, 16.0), (4.0, 18.0)];
+        let model = fit_linear_cost_model("leaf_count", &samples).unwrap();
+
+        assert!((model.base - 10.0).abs() < 1e-9);
+        assert!((model.slope - 2.0).abs() < 1e-9);
+        assert!((model.r_squared - 1.0).abs() < 1e-9);
+        assert_eq!(model.component_name, "leaf_count");
+        assert!((model.predict(10.0) - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_requires_two_distinct_x_values() {
+        let samples = [(5.0, 1.0), (5.0, 2.0), (5.0, 3.0)];
+        assert!(fit_linear_cost_model("batch_size", &samples).is_err());
+    }
+
+    #[test]
+    fn test_fit_handles_noisy_data_with_partial_r_squared() {
+        let samples = [(1.0, 11.0), (2.0, 13.5), (3.0, 16.5), (4.0, 18.0)];
+        let model = fit_linear_cost_model("json_size", &samples).unwrap();
+        assert!(model.r_squared > 0.9 && model.r_squared <= 1.0);
+    }
+
+    #[test]
+    fn test_benchmark_cost_model_discards_warmup_iteration() {
+        use std::cell::RefCell;
+
+        // First call per point returns a spiked "warm-up" value; subsequent
+        // calls return the true linear relationship y = 5 + 3x.
+        let call_count = RefCell::new(0u64);
+        let model = benchmark_cost_model("segments", &[1, 2, 3, 4], 3, |x| {
+            let mut count = call_count.borrow_mut();
+            *count += 1;
+            if *count % 3 == 1 {
+                1_000_000.0 // warm-up outlier, must be discarded
+            } else {
+                5.0 + 3.0 * x as f64
+            }
+        })
+        .unwrap();
+
+        assert!((model.slope - 3.0).abs() < 1e-6);
+        assert!((model.base - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_benchmark_cost_model_requires_at_least_two_repetitions() {
+        let result = benchmark_cost_model("segments", &[1, 2], 1, |x| x as f64);
+        assert!(result.is_err());
+    }
+}
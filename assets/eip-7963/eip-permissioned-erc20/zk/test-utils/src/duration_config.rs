@@ -0,0 +1,94 @@
+use serde::{Deserialize, Deserializer};
+use std::time::Duration;
+
+/// Parse a human-readable duration such as `"3 minutes"`, `"500ms"`,
+/// `"1 second"`, or a named alias like `"half-hourly"`, so scenario
+/// timeouts and per-currency expiry windows can live in a config file
+/// instead of being recompiled as `Duration::from_secs(...)` literals.
+///
+/// Named aliases are matched first, then the input is parsed as a leading
+/// integer followed by a unit suffix (`ms`, `s`/`second(s)`, `m`/`minute(s)`,
+/// `h`/`hour(s)`).
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let trimmed = input.trim();
+
+    match trimmed {
+        "half-hourly" => return Ok(Duration::from_secs(30 * 60)),
+        "twice-daily" => return Ok(Duration::from_secs(12 * 60 * 60)),
+        "hourly" => return Ok(Duration::from_secs(60 * 60)),
+        "daily" => return Ok(Duration::from_secs(24 * 60 * 60)),
+        _ => {}
+    }
+
+    let split_at = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("Duration string has no unit: {}", trimmed))?;
+    let (number, unit) = trimmed.split_at(split_at);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("Invalid duration magnitude: {}", trimmed))?;
+    let unit = unit.trim();
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(number)),
+        "s" | "second" | "seconds" => Ok(Duration::from_secs(number)),
+        "m" | "minute" | "minutes" => Ok(Duration::from_secs(number * 60)),
+        "h" | "hour" | "hours" => Ok(Duration::from_secs(number * 60 * 60)),
+        _ => Err(format!("Unknown duration unit: {}", unit)),
+    }
+}
+
+/// `serde(deserialize_with = "...")` helper for `Duration` fields driven by
+/// human-readable strings (e.g. `max_proof_time: "3 minutes"` in a config
+/// file).
+pub fn deserialize_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    parse_duration(&raw).map_err(serde::de::Error::custom)
+}
+
+/// `serde(deserialize_with = "...")` helper for plain-seconds `u64` fields
+/// driven by human-readable strings.
+pub fn deserialize_duration_secs<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_duration(deserializer).map(|d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_duration("1 second").unwrap(), Duration::from_secs(1));
+        assert_eq!(parse_duration("1s").unwrap(), Duration::from_secs(1));
+        assert_eq!(
+            parse_duration("3 minutes").unwrap(),
+            Duration::from_secs(180)
+        );
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+    }
+
+    #[test]
+    fn test_parse_duration_aliases() {
+        assert_eq!(
+            parse_duration("half-hourly").unwrap(),
+            Duration::from_secs(30 * 60)
+        );
+        assert_eq!(
+            parse_duration("twice-daily").unwrap(),
+            Duration::from_secs(12 * 60 * 60)
+        );
+    }
+
+    #[test]
+    fn test_parse_duration_errors() {
+        assert!(parse_duration("3 fortnights").is_err());
+        assert!(parse_duration("no-number").is_err());
+    }
+}
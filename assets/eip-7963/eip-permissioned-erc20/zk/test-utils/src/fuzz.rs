@@ -0,0 +1,95 @@
+use crate::payment_instruction_generator::PaymentInstructionInput;
+use crate::test_helpers::{assert_outputs_equal, create_test_config, generate_and_verify_proof, TestScenario};
+use crate::{guest_logic, mock_data::MockData, payment_instruction_generator::PaymentInstructionGenerator, proof_validator::ProofValidator};
+
+/// Invariant oracle for honggfuzz-style fuzzing of the validator and guest
+/// pipeline, in the spirit of Substrate's honggfuzz targets: any input that
+/// reaches here must satisfy one of two mutually exclusive outcomes, and
+/// anything else (a panic, or the two outcomes disagreeing) is a bug.
+///
+/// 1. The host validator and the duplicated guest logic must agree on
+///    whether `input` is acceptable.
+/// 2. If both accept it, a full proof generated from `input` must decode to
+///    an output that round-trips through `assert_outputs_equal` against the
+///    guest's own computed output.
+///
+/// Panics on divergence so the fuzzer records it as a crash.
+pub fn check_pipeline_invariant(input: &PaymentInstructionInput) {
+    let host_result = ProofValidator::validate_input_consistency(input);
+    let guest_result = guest_logic::verify_payment_instruction(input);
+
+    match (&host_result, &guest_result) {
+        (Ok(_), Err(e)) => panic!("host validator accepted an input the guest rejected: {}", e),
+        (Err(e), Ok(_)) => panic!("guest accepted an input the host validator rejected: {}", e),
+        _ => {}
+    }
+
+    let Ok(guest_output) = guest_result else {
+        return; // both sides rejected; consistent, nothing further to check
+    };
+
+    let config = create_test_config(TestScenario::Fast);
+    if let Ok((proof_output, _)) = generate_and_verify_proof(input, &config) {
+        assert_outputs_equal(&guest_output, &proof_output);
+    }
+}
+
+/// Fuzz the standalone JSON-format check in isolation; any input must either
+/// be rejected or accepted, never panic.
+pub fn check_json_format_invariant(json_str: &str) {
+    let _ = ProofValidator::validate_json_format(json_str);
+}
+
+/// Fuzz the standalone date-format check in isolation; any input must either
+/// be rejected or accepted, never panic.
+pub fn check_date_format_invariant(date_str: &str) {
+    let _ = ProofValidator::validate_date_format(date_str);
+}
+
+/// Seed corpus drawn from the curated error cases and generator-produced
+/// edge cases, so `cargo hfuzz run` starts from known-interesting inputs
+/// instead of pure noise.
+pub fn seed_corpus() -> Vec<PaymentInstructionInput> {
+    let mut generator = PaymentInstructionGenerator::new();
+    let mut inputs: Vec<PaymentInstructionInput> = MockData::error_cases()
+        .into_iter()
+        .map(|(_, input)| input)
+        .collect();
+    inputs.extend(generator.generate_edge_cases());
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_corpus_is_nonempty() {
+        assert!(!seed_corpus().is_empty());
+    }
+
+    #[test]
+    fn test_check_pipeline_invariant_accepts_valid_input() {
+        let input = MockData::simple_valid_input();
+        check_pipeline_invariant(&input); // must not panic
+    }
+
+    #[test]
+    fn test_check_pipeline_invariant_rejects_mismatched_hash() {
+        let mut input = MockData::simple_valid_input();
+        input.debtor_hash = [0xAB; 32];
+        check_pipeline_invariant(&input); // both sides reject; must not panic
+    }
+
+    #[test]
+    fn test_check_json_format_invariant_never_panics_on_garbage() {
+        check_json_format_invariant("{not json");
+        check_json_format_invariant("");
+    }
+
+    #[test]
+    fn test_check_date_format_invariant_never_panics_on_garbage() {
+        check_date_format_invariant("not-a-date");
+        check_date_format_invariant("20240230");
+    }
+}
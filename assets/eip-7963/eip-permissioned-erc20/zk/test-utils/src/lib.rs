@@ -1,13 +1,35 @@
+pub mod bech32;
+pub mod cost_model;
 pub mod crypto_utils;
+pub mod duration_config;
+pub mod execution_trace;
+pub mod fixtures;
+pub mod fuzz;
+pub mod gas_profiling;
 pub mod guest_logic;
+pub mod hashing;
+pub mod incremental_witness;
 pub mod integration;
 pub mod merkle_tree;
+pub mod mmr;
 pub mod mock_data;
+pub mod note_encryption;
+pub mod pain001_xml;
 pub mod payment_instruction_generator;
+pub mod payment_instruction_tree;
+pub mod payment_invoice;
+pub mod payment_plan;
+pub mod payment_state;
+pub mod poseidon;
+pub mod proof_aggregation;
 pub mod proof_validator;
+pub mod prover_backend;
+pub mod prover_cache;
+pub mod receipt_export;
 pub mod test_helpers; // Simplified integration module
 
 use anyhow;
+use serde::Deserialize;
 
 // Re-export commonly used types
 pub use methods::{METHOD_ELF, METHOD_ID};
@@ -23,11 +45,41 @@ pub use integration::{
 pub type TestResult<T> = Result<T, anyhow::Error>;
 
 // Test configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TestConfig {
     pub enable_logging: bool,
+    #[serde(deserialize_with = "duration_config::deserialize_duration_secs")]
     pub proof_timeout_secs: u64,
     pub max_memory_mb: u64,
+    #[serde(default)]
+    pub proof_mode: ProofMode,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    /// Which `ProverBackend` a caller dispatches proof generation through:
+    /// the local RISC Zero prover, a remote/Bonsai-style service, or a
+    /// dev/mock backend that skips real proving.
+    #[serde(default)]
+    pub backend: prover_backend::BackendKind,
+    /// Per-component gas weights `generate_proof` charges
+    /// `TestMetrics::verification_gas` against, so gas estimates are
+    /// calibrated once and reproduced deterministically rather than read
+    /// off a live EVM trace on whatever machine ran the proof.
+    #[serde(default)]
+    pub gas_weights: test_helpers::GasWeights,
+    /// Directory `generate_and_verify_proof` reads/writes cached receipts
+    /// under when `cache_enabled` is set. See `prover_cache::ReceiptCache`.
+    #[serde(default = "default_cache_dir")]
+    pub cache_dir: std::path::PathBuf,
+    /// Whether `generate_and_verify_proof` consults the receipt cache
+    /// before generating a fresh proof, so repeated batch/stress runs over
+    /// the same input skip re-proving. Off by default so a config that
+    /// doesn't mention caching at all behaves exactly as before.
+    #[serde(default)]
+    pub cache_enabled: bool,
+}
+
+pub(crate) fn default_cache_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join("test-utils-receipt-cache")
 }
 
 impl Default for TestConfig {
@@ -36,10 +88,53 @@ impl Default for TestConfig {
             enable_logging: false,
             proof_timeout_secs: 300, // 5 minutes
             max_memory_mb: 2048,     // 2GB
+            proof_mode: ProofMode::default(),
+            retry_policy: RetryPolicy::default(),
+            backend: prover_backend::BackendKind::default(),
+            gas_weights: test_helpers::GasWeights::default(),
+            cache_dir: default_cache_dir(),
+            cache_enabled: false,
+        }
+    }
+}
+
+/// Retry policy for transient prover failures (timeout, host OOM, runner
+/// crash) unrelated to the witness itself, applied with exponential
+/// backoff between attempts.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    #[serde(deserialize_with = "duration_config::deserialize_duration_secs")]
+    pub initial_backoff_secs: u64,
+    pub backoff_multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_secs: 1,
+            backoff_multiplier: 2,
         }
     }
 }
 
+/// Which RISC Zero receipt kind `generate_proof` should produce: the default
+/// STARK-based composite receipt, a recursively compressed succinct receipt,
+/// or a Groth16/SNARK receipt small enough for on-chain verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ProofMode {
+    Composite,
+    Succinct,
+    Groth16,
+}
+
+impl Default for ProofMode {
+    fn default() -> Self {
+        ProofMode::Composite
+    }
+}
+
 // Initialize test environment
 pub fn init_test_env(config: TestConfig) {
     if config.enable_logging {
@@ -57,5 +152,21 @@ mod tests {
         assert_eq!(config.proof_timeout_secs, 300);
         assert_eq!(config.max_memory_mb, 2048);
         assert!(!config.enable_logging);
+        assert_eq!(config.proof_mode, ProofMode::Composite);
+        assert_eq!(config.retry_policy.max_attempts, 3);
+        assert_eq!(config.backend, prover_backend::BackendKind::Local);
+    }
+
+    #[test]
+    fn test_config_from_human_readable_toml() {
+        let toml = r#"
+            enable_logging = true
+            proof_timeout_secs = "3 minutes"
+            max_memory_mb = 4096
+        "#;
+        let config: TestConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.proof_timeout_secs, 180);
+        assert_eq!(config.max_memory_mb, 4096);
+        assert!(config.enable_logging);
     }
 }
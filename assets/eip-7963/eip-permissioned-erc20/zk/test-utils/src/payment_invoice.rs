@@ -0,0 +1,303 @@
+//! BOLT11-style encoding of a proven payment instruction's public journal
+//! fields into a compact, human-shareable invoice string, suitable for a
+//! URI or QR code without shipping the full journal bytes.
+//!
+//! The format mirrors lightning's BOLT11: a human-readable prefix carrying
+//! a currency indicator and a decimal amount with an `m`/`u`/`n`
+//! (milli/micro/nano) multiplier letter, the separator `1`, a data part of
+//! tagged fields packed into 5-bit groups, and a trailing bech32 checksum
+//! computed with the standard polymod over the whole string.
+
+use crate::bech32;
+use crate::crypto_utils::HashSuite;
+use crate::payment_instruction_generator::PaymentInstructionOutput;
+use std::collections::HashMap;
+
+const HRP_PREFIX: &str = "pay";
+
+const TAG_ROOT: u8 = 1;
+const TAG_DEBTOR_HASH: u8 = 2;
+const TAG_CREDITOR_HASH: u8 = 3;
+const TAG_MIN_AMOUNT: u8 = 4;
+const TAG_MAX_AMOUNT: u8 = 5;
+const TAG_CURRENCY_HASH: u8 = 6;
+const TAG_EXPIRY: u8 = 7;
+const TAG_HASH_SUITE: u8 = 8;
+
+/// Encode `output`'s public journal fields into a BOLT11-style invoice
+/// string.
+pub fn encode(output: &PaymentInstructionOutput) -> Result<String, String> {
+    let (amount_value, multiplier) = encode_amount(output.max_amount_milli);
+    let hrp = format!(
+        "{}{}{}{}",
+        HRP_PREFIX,
+        hex_prefix(&output.currency_hash),
+        amount_value,
+        multiplier
+    );
+
+    let mut data = Vec::new();
+    push_field(&mut data, TAG_ROOT, &output.root)?;
+    push_field(&mut data, TAG_DEBTOR_HASH, &output.debtor_hash)?;
+    push_field(&mut data, TAG_CREDITOR_HASH, &output.creditor_hash)?;
+    push_field(&mut data, TAG_MIN_AMOUNT, &output.min_amount_milli.to_be_bytes())?;
+    push_field(&mut data, TAG_MAX_AMOUNT, &output.max_amount_milli.to_be_bytes())?;
+    push_field(&mut data, TAG_CURRENCY_HASH, &output.currency_hash)?;
+    push_field(&mut data, TAG_EXPIRY, &output.expiry.to_be_bytes())?;
+    push_field(&mut data, TAG_HASH_SUITE, &[output.hash_suite.id()])?;
+
+    Ok(bech32::encode(&hrp, &data))
+}
+
+/// Decode a BOLT11-style invoice string back into its `PaymentInstructionOutput`,
+/// rejecting a failed checksum, a mismatched hrp/field amount, a field whose
+/// reconstructed length doesn't match its declared length, or an `expiry`
+/// already in the past relative to `now`.
+pub fn decode(invoice: &str, now: u64) -> Result<PaymentInstructionOutput, String> {
+    let (hrp, data) = bech32::decode(invoice)?;
+    let fields = parse_fields(&data)?;
+
+    let output = PaymentInstructionOutput {
+        root: field_32(&fields, TAG_ROOT)?,
+        debtor_hash: field_32(&fields, TAG_DEBTOR_HASH)?,
+        creditor_hash: field_32(&fields, TAG_CREDITOR_HASH)?,
+        min_amount_milli: field_u64(&fields, TAG_MIN_AMOUNT)?,
+        max_amount_milli: field_u64(&fields, TAG_MAX_AMOUNT)?,
+        currency_hash: field_32(&fields, TAG_CURRENCY_HASH)?,
+        expiry: field_u64(&fields, TAG_EXPIRY)?,
+        hash_suite: HashSuite::from_id(field_u8(&fields, TAG_HASH_SUITE)?)?,
+    };
+
+    let (_currency_indicator, hrp_amount_value, multiplier) = parse_hrp(&hrp)?;
+    let hrp_amount_milli = decode_amount(hrp_amount_value, multiplier)?;
+    if hrp_amount_milli != output.max_amount_milli {
+        return Err(format!(
+            "hrp amount {} does not match encoded max_amount_milli {}",
+            hrp_amount_milli, output.max_amount_milli
+        ));
+    }
+
+    if output.expiry < now {
+        return Err(format!(
+            "invoice expired: expiry {} is before {}",
+            output.expiry, now
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Pack one TLV field into `data`: a one-group tag, a big-endian length (in
+/// 5-bit groups) spanning two groups, then the field bytes converted from
+/// 8-bit to 5-bit groups with zero-padding on the final group.
+fn push_field(data: &mut Vec<u8>, tag: u8, bytes: &[u8]) -> Result<(), String> {
+    let packed = bech32::convert_bits(bytes, 8, 5, true)?;
+    if packed.len() > 0x3ff {
+        return Err(format!(
+            "field for tag {} is too large to encode ({} groups)",
+            tag,
+            packed.len()
+        ));
+    }
+    data.push(tag);
+    data.push((packed.len() >> 5) as u8);
+    data.push((packed.len() & 0x1f) as u8);
+    data.extend_from_slice(&packed);
+    Ok(())
+}
+
+/// Parse the 5-bit data part into a tag -> decoded-bytes map, validating
+/// that every declared field length fits within the remaining data.
+fn parse_fields(data: &[u8]) -> Result<HashMap<u8, Vec<u8>>, String> {
+    let mut fields = HashMap::new();
+    let mut i = 0;
+    while i < data.len() {
+        if i + 3 > data.len() {
+            return Err("truncated TLV field header".to_string());
+        }
+        let tag = data[i];
+        let length = ((data[i + 1] as usize) << 5) | (data[i + 2] as usize);
+        i += 3;
+        if i + length > data.len() {
+            return Err(format!(
+                "field length {} for tag {} exceeds remaining data",
+                length, tag
+            ));
+        }
+        let bytes = bech32::convert_bits(&data[i..i + length], 5, 8, false)?;
+        fields.insert(tag, bytes);
+        i += length;
+    }
+    Ok(fields)
+}
+
+fn field_32(fields: &HashMap<u8, Vec<u8>>, tag: u8) -> Result<[u8; 32], String> {
+    let bytes = fields
+        .get(&tag)
+        .ok_or_else(|| format!("missing field for tag {}", tag))?;
+    if bytes.len() != 32 {
+        return Err(format!(
+            "field for tag {} has length {}, expected 32",
+            tag,
+            bytes.len()
+        ));
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(bytes);
+    Ok(out)
+}
+
+fn field_u64(fields: &HashMap<u8, Vec<u8>>, tag: u8) -> Result<u64, String> {
+    let bytes = fields
+        .get(&tag)
+        .ok_or_else(|| format!("missing field for tag {}", tag))?;
+    if bytes.len() != 8 {
+        return Err(format!(
+            "field for tag {} has length {}, expected 8",
+            tag,
+            bytes.len()
+        ));
+    }
+    let mut out = [0u8; 8];
+    out.copy_from_slice(bytes);
+    Ok(u64::from_be_bytes(out))
+}
+
+fn field_u8(fields: &HashMap<u8, Vec<u8>>, tag: u8) -> Result<u8, String> {
+    let bytes = fields
+        .get(&tag)
+        .ok_or_else(|| format!("missing field for tag {}", tag))?;
+    if bytes.len() != 1 {
+        return Err(format!(
+            "field for tag {} has length {}, expected 1",
+            tag,
+            bytes.len()
+        ));
+    }
+    Ok(bytes[0])
+}
+
+/// Pick the coarsest `m`/`u`/`n` multiplier (milli/micro/nano scaling of the
+/// milli-denominated amount) that represents `milli` exactly, the way BOLT11
+/// picks the shortest lossless decimal representation of an amount.
+fn encode_amount(milli: u64) -> (u64, char) {
+    if milli != 0 && milli % 1_000_000 == 0 {
+        (milli / 1_000_000, 'n')
+    } else if milli != 0 && milli % 1_000 == 0 {
+        (milli / 1_000, 'u')
+    } else {
+        (milli, 'm')
+    }
+}
+
+fn decode_amount(value: u64, multiplier: char) -> Result<u64, String> {
+    match multiplier {
+        'n' => Ok(value * 1_000_000),
+        'u' => Ok(value * 1_000),
+        'm' => Ok(value),
+        other => Err(format!("unknown amount multiplier '{}'", other)),
+    }
+}
+
+/// Split the hrp into its currency indicator (8 hex characters right after
+/// the `pay` prefix), its decimal amount value, and its trailing multiplier
+/// letter.
+fn parse_hrp(hrp: &str) -> Result<(String, u64, char), String> {
+    let rest = hrp
+        .strip_prefix(HRP_PREFIX)
+        .ok_or_else(|| format!("invoice hrp must start with '{}': {}", HRP_PREFIX, hrp))?;
+    if rest.len() < 8 + 2 {
+        return Err(format!("invoice hrp is too short: {}", hrp));
+    }
+    let (currency_indicator, amount_part) = rest.split_at(8);
+    let multiplier = amount_part
+        .chars()
+        .last()
+        .ok_or_else(|| format!("invoice hrp is missing an amount multiplier: {}", hrp))?;
+    let digits = &amount_part[..amount_part.len() - 1];
+    let amount_value: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid amount in invoice hrp: {}", hrp))?;
+    Ok((currency_indicator.to_string(), amount_value, multiplier))
+}
+
+fn hex_prefix(hash: &[u8; 32]) -> String {
+    hash[..4].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_data::MockData;
+
+    fn sample_output() -> PaymentInstructionOutput {
+        let input = MockData::simple_valid_input();
+        PaymentInstructionOutput {
+            root: input.root,
+            debtor_hash: input.debtor_hash,
+            creditor_hash: input.creditor_hash,
+            min_amount_milli: input.min_amount_milli,
+            max_amount_milli: input.max_amount_milli,
+            currency_hash: input.currency_hash,
+            expiry: input.expiry,
+            hash_suite: input.hash_suite,
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let output = sample_output();
+        let invoice = encode(&output).unwrap();
+        assert!(invoice.starts_with(HRP_PREFIX));
+
+        let decoded = decode(&invoice, 0).unwrap();
+        assert_eq!(decoded.root, output.root);
+        assert_eq!(decoded.debtor_hash, output.debtor_hash);
+        assert_eq!(decoded.creditor_hash, output.creditor_hash);
+        assert_eq!(decoded.min_amount_milli, output.min_amount_milli);
+        assert_eq!(decoded.max_amount_milli, output.max_amount_milli);
+        assert_eq!(decoded.currency_hash, output.currency_hash);
+        assert_eq!(decoded.expiry, output.expiry);
+        assert_eq!(decoded.hash_suite, output.hash_suite);
+    }
+
+    #[test]
+    fn test_decode_rejects_corrupted_checksum() {
+        let output = sample_output();
+        let mut invoice = encode(&output).unwrap().into_bytes();
+        let last = invoice.len() - 1;
+        invoice[last] = if invoice[last] == b'q' { b'p' } else { b'q' };
+        let invoice = String::from_utf8(invoice).unwrap();
+
+        assert!(decode(&invoice, 0).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_expired_invoice() {
+        let output = sample_output();
+        let invoice = encode(&output).unwrap();
+
+        assert!(decode(&invoice, output.expiry + 1).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_field_data() {
+        let output = sample_output();
+        let invoice = encode(&output).unwrap();
+        let (hrp, data) = bech32::decode(&invoice).unwrap();
+        let truncated_data = &data[..data.len() - 1];
+        let truncated_invoice = bech32::encode(&hrp, truncated_data);
+
+        assert!(decode(&truncated_invoice, 0).is_err());
+    }
+
+    #[test]
+    fn test_encode_amount_prefers_coarsest_exact_multiplier() {
+        assert_eq!(encode_amount(5_000_000), (5, 'n'));
+        assert_eq!(encode_amount(5_000), (5, 'u'));
+        assert_eq!(encode_amount(5), (5, 'm'));
+        assert_eq!(decode_amount(5, 'n').unwrap(), 5_000_000);
+        assert_eq!(decode_amount(5, 'u').unwrap(), 5_000);
+        assert_eq!(decode_amount(5, 'm').unwrap(), 5);
+    }
+}
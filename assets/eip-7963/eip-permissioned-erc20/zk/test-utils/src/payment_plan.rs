@@ -0,0 +1,147 @@
+use crate::payment_instruction_generator::PaymentInstructionInput;
+
+/// A witness presented to satisfy a release condition in a `PaymentPlan`,
+/// modeled on Solana's `payment_plan` (Payment/Witness/Condition) primitive.
+/// The same variants double as the conditions a plan is built with and the
+/// witnesses presented at settlement time to satisfy them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Witness {
+    /// Satisfied once `today` (as `YYYYMMDD`) reaches or passes this threshold.
+    Timestamp(u64),
+    /// Satisfied when a witness carrying a signature from this pubkey is presented.
+    Signature(String),
+}
+
+impl Witness {
+    fn is_satisfied(&self, presented: &[Witness], today: u64) -> bool {
+        match self {
+            Witness::Timestamp(threshold) => today >= *threshold,
+            Witness::Signature(pubkey) => presented
+                .iter()
+                .any(|w| matches!(w, Witness::Signature(signed) if signed == pubkey)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Witness::Timestamp(threshold) => format!("Timestamp({}) not yet reached", threshold),
+            Witness::Signature(pubkey) => format!("Signature({}) not presented", pubkey),
+        }
+    }
+}
+
+/// A conditional payment instruction: immediately payable, gated behind a
+/// single witness, or resolvable through either of two sub-plans. Turns the
+/// single-shot `PaymentInstructionInput` into a primitive for escrow and
+/// scheduled settlement.
+#[derive(Debug, Clone)]
+pub enum PaymentPlan {
+    Pay(PaymentInstructionInput),
+    After(Witness, Box<PaymentPlan>),
+    Or(Box<PaymentPlan>, Box<PaymentPlan>),
+}
+
+impl PaymentPlan {
+    pub fn pay(input: PaymentInstructionInput) -> Self {
+        PaymentPlan::Pay(input)
+    }
+
+    pub fn after(condition: Witness, payment: PaymentPlan) -> Self {
+        PaymentPlan::After(condition, Box::new(payment))
+    }
+
+    pub fn or(a: PaymentPlan, b: PaymentPlan) -> Self {
+        PaymentPlan::Or(Box::new(a), Box::new(b))
+    }
+
+    /// Resolve this plan against the presented witnesses and the current
+    /// date (`YYYYMMDD`), returning the now-payable input or the list of
+    /// condition descriptions still outstanding.
+    pub fn apply(
+        &self,
+        witnesses: &[Witness],
+        today: u64,
+    ) -> Result<&PaymentInstructionInput, Vec<String>> {
+        match self {
+            PaymentPlan::Pay(input) => Ok(input),
+            PaymentPlan::After(condition, inner) => {
+                if condition.is_satisfied(witnesses, today) {
+                    inner.apply(witnesses, today)
+                } else {
+                    Err(vec![condition.describe()])
+                }
+            }
+            PaymentPlan::Or(a, b) => match a.apply(witnesses, today) {
+                Ok(input) => Ok(input),
+                Err(mut unmet_a) => match b.apply(witnesses, today) {
+                    Ok(input) => Ok(input),
+                    Err(unmet_b) => {
+                        unmet_a.extend(unmet_b);
+                        Err(unmet_a)
+                    }
+                },
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_data::MockData;
+
+    #[test]
+    fn test_pay_resolves_immediately() {
+        let input = MockData::simple_valid_input();
+        let plan = PaymentPlan::pay(input.clone());
+        let resolved = plan.apply(&[], 20240101).unwrap();
+        assert_eq!(resolved.root, input.root);
+    }
+
+    #[test]
+    fn test_after_timestamp_blocks_until_threshold() {
+        let plan = PaymentPlan::after(
+            Witness::Timestamp(20250101),
+            PaymentPlan::pay(MockData::simple_valid_input()),
+        );
+
+        assert!(plan.apply(&[], 20241231).is_err());
+        assert!(plan.apply(&[], 20250101).is_ok());
+    }
+
+    #[test]
+    fn test_after_signature_requires_matching_witness() {
+        let plan = PaymentPlan::after(
+            Witness::Signature("alice-pubkey".to_string()),
+            PaymentPlan::pay(MockData::simple_valid_input()),
+        );
+
+        let err = plan.apply(&[], 20240101).unwrap_err();
+        assert!(err[0].contains("alice-pubkey"));
+
+        let witnesses = [Witness::Signature("alice-pubkey".to_string())];
+        assert!(plan.apply(&witnesses, 20240101).is_ok());
+    }
+
+    #[test]
+    fn test_or_resolves_when_either_branch_is_satisfied() {
+        let plan = PaymentPlan::or(
+            PaymentPlan::after(
+                Witness::Timestamp(20990101),
+                PaymentPlan::pay(MockData::simple_valid_input()),
+            ),
+            PaymentPlan::after(
+                Witness::Signature("bob-pubkey".to_string()),
+                PaymentPlan::pay(MockData::simple_valid_input()),
+            ),
+        );
+
+        // Neither branch satisfied yet
+        let err = plan.apply(&[], 20240101).unwrap_err();
+        assert_eq!(err.len(), 2);
+
+        // Satisfying only the second branch resolves the plan
+        let witnesses = [Witness::Signature("bob-pubkey".to_string())];
+        assert!(plan.apply(&witnesses, 20240101).is_ok());
+    }
+}
@@ -0,0 +1,237 @@
+use crate::payment_instruction_generator::PaymentInstructionInput;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// Lifecycle state of a validated payment instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentState {
+    Created,
+    Settled,
+    Disputed,
+    Reversed,
+}
+
+/// A single lifecycle event in an ordered stream, mirroring the
+/// deposit/dispute/resolve/chargeback verbs of a toy transaction engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentEvent {
+    Deposit,
+    Dispute,
+    Resolve,
+    Chargeback,
+}
+
+#[derive(Debug, Clone)]
+struct PaymentRecord {
+    state: PaymentState,
+    debtor_hash: [u8; 32],
+    amount_milli: u64,
+}
+
+/// Per-debtor aggregate of funds currently held (disputed) vs. available,
+/// in `milli` units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DebtorBalance {
+    pub available_milli: u64,
+    pub held_milli: u64,
+}
+
+/// In-memory store tracking each payment instruction through
+/// `Created -> Settled -> Disputed -> (Settled | Reversed)`, keyed by the
+/// instruction's Merkle `root`, modeled on a toy ledger's transaction engine.
+#[derive(Debug, Default)]
+pub struct PaymentStateStore {
+    payments: HashMap<[u8; 32], PaymentRecord>,
+    balances: HashMap<[u8; 32], DebtorBalance>,
+}
+
+impl PaymentStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a newly validated instruction as `Created`.
+    pub fn create(&mut self, input: &PaymentInstructionInput) -> Result<()> {
+        if self.payments.contains_key(&input.root) {
+            return Err(anyhow!("Payment {:?} already exists", input.root));
+        }
+        self.payments.insert(
+            input.root,
+            PaymentRecord {
+                state: PaymentState::Created,
+                debtor_hash: input.debtor_hash,
+                amount_milli: input.amount_value,
+            },
+        );
+        Ok(())
+    }
+
+    /// Settle a `Created` payment, crediting its amount to the debtor's
+    /// available balance.
+    pub fn settle(&mut self, root: &[u8; 32]) -> Result<()> {
+        let record = self.record_mut(root)?;
+        if record.state != PaymentState::Created {
+            return Err(anyhow!(
+                "Cannot settle payment {:?} from state {:?}",
+                root,
+                record.state
+            ));
+        }
+        record.state = PaymentState::Settled;
+        let (debtor_hash, amount_milli) = (record.debtor_hash, record.amount_milli);
+        self.balances.entry(debtor_hash).or_default().available_milli += amount_milli;
+        Ok(())
+    }
+
+    /// Dispute a `Settled` payment, moving its amount from available to held.
+    pub fn dispute(&mut self, root: &[u8; 32]) -> Result<()> {
+        let record = self.record_mut(root)?;
+        if record.state != PaymentState::Settled {
+            return Err(anyhow!(
+                "Cannot dispute payment {:?} from state {:?}",
+                root,
+                record.state
+            ));
+        }
+        record.state = PaymentState::Disputed;
+        let (debtor_hash, amount_milli) = (record.debtor_hash, record.amount_milli);
+        let balance = self.balances.entry(debtor_hash).or_default();
+        balance.available_milli -= amount_milli;
+        balance.held_milli += amount_milli;
+        Ok(())
+    }
+
+    /// Resolve a dispute in the debtor's favor, returning the payment to
+    /// `Settled` and releasing the held amount back to available.
+    pub fn resolve(&mut self, root: &[u8; 32]) -> Result<()> {
+        let record = self.record_mut(root)?;
+        if record.state != PaymentState::Disputed {
+            return Err(anyhow!(
+                "Cannot resolve payment {:?} from state {:?}",
+                root,
+                record.state
+            ));
+        }
+        record.state = PaymentState::Settled;
+        let (debtor_hash, amount_milli) = (record.debtor_hash, record.amount_milli);
+        let balance = self.balances.entry(debtor_hash).or_default();
+        balance.held_milli -= amount_milli;
+        balance.available_milli += amount_milli;
+        Ok(())
+    }
+
+    /// Reverse a disputed payment permanently via chargeback, removing the
+    /// held amount without returning it to available.
+    pub fn chargeback(&mut self, root: &[u8; 32]) -> Result<()> {
+        let record = self.record_mut(root)?;
+        if record.state != PaymentState::Disputed {
+            return Err(anyhow!(
+                "Cannot chargeback payment {:?} from state {:?}",
+                root,
+                record.state
+            ));
+        }
+        record.state = PaymentState::Reversed;
+        let (debtor_hash, amount_milli) = (record.debtor_hash, record.amount_milli);
+        self.balances.entry(debtor_hash).or_default().held_milli -= amount_milli;
+        Ok(())
+    }
+
+    /// Apply one lifecycle event to the payment identified by `input.root`,
+    /// creating the record on first `Deposit` and requiring it to already
+    /// exist for every subsequent event.
+    pub fn apply(&mut self, input: &PaymentInstructionInput, event: PaymentEvent) -> Result<()> {
+        match event {
+            PaymentEvent::Deposit => {
+                self.create(input)?;
+                self.settle(&input.root)
+            }
+            PaymentEvent::Dispute => self.dispute(&input.root),
+            PaymentEvent::Resolve => self.resolve(&input.root),
+            PaymentEvent::Chargeback => self.chargeback(&input.root),
+        }
+    }
+
+    pub fn state_of(&self, root: &[u8; 32]) -> Option<PaymentState> {
+        self.payments.get(root).map(|r| r.state)
+    }
+
+    pub fn balance_of(&self, debtor_hash: &[u8; 32]) -> DebtorBalance {
+        self.balances.get(debtor_hash).copied().unwrap_or_default()
+    }
+
+    fn record_mut(&mut self, root: &[u8; 32]) -> Result<&mut PaymentRecord> {
+        self.payments
+            .get_mut(root)
+            .ok_or_else(|| anyhow!("Unknown payment {:?}", root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_data::MockData;
+
+    #[test]
+    fn test_deposit_then_dispute_moves_funds_to_held() {
+        let input = MockData::simple_valid_input();
+        let mut store = PaymentStateStore::new();
+
+        store.apply(&input, PaymentEvent::Deposit).unwrap();
+        let balance = store.balance_of(&input.debtor_hash);
+        assert_eq!(balance.available_milli, input.amount_value);
+        assert_eq!(balance.held_milli, 0);
+
+        store.apply(&input, PaymentEvent::Dispute).unwrap();
+        let balance = store.balance_of(&input.debtor_hash);
+        assert_eq!(balance.available_milli, 0);
+        assert_eq!(balance.held_milli, input.amount_value);
+        assert_eq!(store.state_of(&input.root), Some(PaymentState::Disputed));
+    }
+
+    #[test]
+    fn test_dispute_then_resolve_restores_available_balance() {
+        let input = MockData::simple_valid_input();
+        let mut store = PaymentStateStore::new();
+
+        for event in MockData::dispute_then_resolve_events() {
+            store.apply(&input, event).unwrap();
+        }
+
+        let balance = store.balance_of(&input.debtor_hash);
+        assert_eq!(balance.available_milli, input.amount_value);
+        assert_eq!(balance.held_milli, 0);
+        assert_eq!(store.state_of(&input.root), Some(PaymentState::Settled));
+    }
+
+    #[test]
+    fn test_dispute_then_chargeback_reverses_payment() {
+        let input = MockData::simple_valid_input();
+        let mut store = PaymentStateStore::new();
+
+        for event in MockData::dispute_then_chargeback_events() {
+            store.apply(&input, event).unwrap();
+        }
+
+        let balance = store.balance_of(&input.debtor_hash);
+        assert_eq!(balance.available_milli, 0);
+        assert_eq!(balance.held_milli, 0);
+        assert_eq!(store.state_of(&input.root), Some(PaymentState::Reversed));
+    }
+
+    #[test]
+    fn test_illegal_transitions_are_rejected() {
+        let input = MockData::simple_valid_input();
+        let mut store = PaymentStateStore::new();
+
+        // Disputing a payment that was never created/settled
+        assert!(store.dispute(&input.root).is_err());
+
+        store.apply(&input, PaymentEvent::Deposit).unwrap();
+
+        // Resolving a payment that was never disputed
+        assert!(store.resolve(&input.root).is_err());
+        // Charging back a payment that was never disputed
+        assert!(store.chargeback(&input.root).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::crypto_utils::{compute_leaf_hash, HashSuite};
+use crate::payment_instruction_generator::{PaymentInstructionInput, PaymentInstructionOutput};
+use crate::test_helpers::{generate_proof, verify_receipt};
+use crate::{TestConfig, TestResult};
+use risc0_zkvm::{default_prover, ExecutorEnv, Receipt};
+
+/// Domain tag distinguishing an aggregate-batch leaf commitment from other
+/// `compute_leaf_hash` callers (the payment Merkle tree, invoice fields, ...).
+const AGGREGATE_LEAF_TAG: u8 = 0x10;
+
+/// Output committed by the aggregator guest: a single succinct attestation
+/// that every payment instruction output in the batch was accepted by the
+/// payment instruction guest program.
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct AggregatedBatchOutput {
+    pub batch_size: u32,
+    pub combined_commitment: [u8; 32],
+    pub total_amount_milli: u64,
+}
+
+/// Recursively aggregate a batch of already-generated payment instruction
+/// receipts into a single succinct receipt, using RISC Zero composition:
+/// each inner receipt is added as an assumption and verified inside the
+/// aggregator guest, rather than re-proving the underlying statements.
+pub fn aggregate_proofs(
+    receipts: &[Receipt],
+    _config: &TestConfig,
+) -> TestResult<(Receipt, AggregatedBatchOutput)> {
+    let mut outputs = Vec::with_capacity(receipts.len());
+    let mut env_builder = ExecutorEnv::builder();
+
+    for receipt in receipts {
+        let output: PaymentInstructionOutput = verify_receipt(receipt)?;
+        outputs.push(output);
+        env_builder.add_assumption(receipt.clone());
+    }
+
+    let env = env_builder.write(&outputs)?.build()?;
+
+    let prover = default_prover();
+    let prove_info = prover.prove(env, aggregator_methods::AGGREGATOR_ELF)?;
+    let receipt = prove_info.receipt;
+
+    receipt.verify(aggregator_methods::AGGREGATOR_ID)?;
+    let aggregated: AggregatedBatchOutput = receipt.journal.decode()?;
+
+    Ok((receipt, aggregated))
+}
+
+/// Cost and membership data returned alongside an aggregate receipt: how
+/// much smaller the single aggregate proof is than the sum of the batch's
+/// individual proofs, and the leaf commitments a verifier can check any one
+/// instruction's inclusion against without needing the other inputs.
+#[derive(Debug, Clone)]
+pub struct AggregateProofMetrics {
+    pub aggregate_proof_size_bytes: usize,
+    pub summed_individual_proof_size_bytes: usize,
+    pub leaf_commitments: Vec<[u8; 32]>,
+}
+
+impl AggregateProofMetrics {
+    /// Bytes a verifier saves by checking the one aggregate proof instead of
+    /// every individual proof in the batch.
+    pub fn bytes_saved(&self) -> usize {
+        self.summed_individual_proof_size_bytes
+            .saturating_sub(self.aggregate_proof_size_bytes)
+    }
+}
+
+/// Prove a batch of payment instructions independently, then fold the
+/// resulting receipts into a single recursive proof. Each child receipt is
+/// verified before its output is folded in (both here, while computing leaf
+/// commitments, and again inside `aggregate_proofs` itself), so a batch
+/// containing even one receipt that fails verification is rejected outright
+/// rather than silently excluded from the aggregate.
+pub fn generate_aggregate_proof(
+    inputs: &[PaymentInstructionInput],
+    config: &TestConfig,
+) -> TestResult<(Receipt, AggregatedBatchOutput, AggregateProofMetrics)> {
+    let mut receipts = Vec::with_capacity(inputs.len());
+    let mut summed_individual_proof_size_bytes = 0usize;
+
+    for input in inputs {
+        let (receipt, metrics) = generate_proof(input, config)?;
+        summed_individual_proof_size_bytes += metrics.proof_size_bytes;
+        receipts.push(receipt);
+    }
+
+    let mut leaf_commitments = Vec::with_capacity(receipts.len());
+    for receipt in &receipts {
+        let output: PaymentInstructionOutput = verify_receipt(receipt)?;
+        leaf_commitments.push(compute_leaf_hash(output.encode().as_bytes(), AGGREGATE_LEAF_TAG));
+    }
+
+    let (aggregate_receipt, aggregated) = aggregate_proofs(&receipts, config)?;
+
+    let aggregate_proof_size_bytes = bincode::serialize(&aggregate_receipt)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0);
+
+    let metrics = AggregateProofMetrics {
+        aggregate_proof_size_bytes,
+        summed_individual_proof_size_bytes,
+        leaf_commitments,
+    };
+
+    Ok((aggregate_receipt, aggregated, metrics))
+}
+
+/// Check whether `output` is one of the instructions folded into an
+/// aggregate proof, given the `leaf_commitments` `generate_aggregate_proof`
+/// returned alongside it. A verifier only needs the claimed output and the
+/// published commitment list, not the original batch of inputs.
+pub fn is_output_included(output: &PaymentInstructionOutput, leaf_commitments: &[[u8; 32]]) -> bool {
+    let leaf = compute_leaf_hash(output.encode().as_bytes(), AGGREGATE_LEAF_TAG);
+    leaf_commitments.contains(&leaf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_output(expiry: u64) -> PaymentInstructionOutput {
+        PaymentInstructionOutput {
+            root: [1u8; 32],
+            debtor_hash: [2u8; 32],
+            creditor_hash: [3u8; 32],
+            min_amount_milli: 1_000,
+            max_amount_milli: 5_000,
+            currency_hash: [4u8; 32],
+            expiry,
+            hash_suite: HashSuite::default(),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_proof_metrics_bytes_saved() {
+        let metrics = AggregateProofMetrics {
+            aggregate_proof_size_bytes: 300,
+            summed_individual_proof_size_bytes: 1_000,
+            leaf_commitments: vec![],
+        };
+        assert_eq!(metrics.bytes_saved(), 700);
+    }
+
+    #[test]
+    fn test_aggregate_proof_metrics_bytes_saved_never_negative() {
+        let metrics = AggregateProofMetrics {
+            aggregate_proof_size_bytes: 1_000,
+            summed_individual_proof_size_bytes: 300,
+            leaf_commitments: vec![],
+        };
+        assert_eq!(metrics.bytes_saved(), 0);
+    }
+
+    #[test]
+    fn test_is_output_included_matches_only_its_own_leaf() {
+        let included = sample_output(1_700_000_000);
+        let other = sample_output(1_800_000_000);
+        let leaf_commitments = vec![compute_leaf_hash(
+            included.encode().as_bytes(),
+            AGGREGATE_LEAF_TAG,
+        )];
+
+        assert!(is_output_included(&included, &leaf_commitments));
+        assert!(!is_output_included(&other, &leaf_commitments));
+    }
+
+    #[test]
+    fn test_aggregated_batch_output_decodes_totals() {
+        let output = AggregatedBatchOutput {
+            batch_size: 3,
+            combined_commitment: [7u8; 32],
+            total_amount_milli: 4500,
+        };
+        assert_eq!(output.batch_size, 3);
+        assert_eq!(output.total_amount_milli, 4500);
+    }
+}
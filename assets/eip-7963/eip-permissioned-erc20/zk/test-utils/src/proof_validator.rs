@@ -1,15 +1,22 @@
-use crate::crypto_utils::{canonicalize_json, keccak256};
+use crate::crypto_utils::canonicalize_json;
+use crate::merkle_tree::{MerkleProof, MerkleTree};
 use crate::payment_instruction_generator::{PaymentInstructionInput, PaymentInstructionOutput};
+use crate::payment_plan::{PaymentPlan, Witness};
 use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
 
 /// Comprehensive proof validator for payment instruction inputs and outputs
 pub struct ProofValidator;
 
 impl ProofValidator {
-    /// Validate that an input is internally consistent
+    /// Validate that an input is internally consistent. Hashes are recomputed
+    /// under the suite the input itself declares (`input.hash_suite`), rather
+    /// than assuming a fixed Poseidon+keccak pairing.
     pub fn validate_input_consistency(input: &PaymentInstructionInput) -> Result<()> {
+        let suite = input.hash_suite;
+
         // 1. Validate debtor hash
-        let computed_debtor_hash = keccak256(canonicalize_json(&input.debtor_data).as_bytes());
+        let computed_debtor_hash = suite.field_hash(canonicalize_json(&input.debtor_data).as_bytes());
         if computed_debtor_hash != input.debtor_hash {
             return Err(anyhow!(
                 "Debtor hash mismatch: computed {:?}, expected {:?}",
@@ -19,7 +26,7 @@ impl ProofValidator {
         }
 
         // 2. Validate creditor hash
-        let computed_creditor_hash = keccak256(canonicalize_json(&input.creditor_data).as_bytes());
+        let computed_creditor_hash = suite.field_hash(canonicalize_json(&input.creditor_data).as_bytes());
         if computed_creditor_hash != input.creditor_hash {
             return Err(anyhow!(
                 "Creditor hash mismatch: computed {:?}, expected {:?}",
@@ -29,7 +36,7 @@ impl ProofValidator {
         }
 
         // 3. Validate currency hash
-        let computed_currency_hash = keccak256(input.currency.as_bytes());
+        let computed_currency_hash = suite.field_hash(input.currency.as_bytes());
         if computed_currency_hash != input.currency_hash {
             return Err(anyhow!(
                 "Currency hash mismatch: computed {:?}, expected {:?}",
@@ -55,11 +62,12 @@ impl ProofValidator {
         }
 
         // 5. Validate expiry format and consistency
-        let parsed_expiry = input
-            .execution_date
-            .replace("-", "")
-            .parse::<u64>()
-            .map_err(|_| anyhow!("Invalid execution date format: {}", input.execution_date))?;
+        let execution_date = Self::parse_execution_date(&input.execution_date)?;
+        let parsed_expiry: u64 = execution_date
+            .format("%Y%m%d")
+            .to_string()
+            .parse()
+            .expect("NaiveDate::format(\"%Y%m%d\") always yields 8 ASCII digits");
         if parsed_expiry != input.expiry {
             return Err(anyhow!(
                 "Expiry mismatch: parsed {}, expected {}",
@@ -68,6 +76,66 @@ impl ProofValidator {
             ));
         }
 
+        // 6. Validate Merkle proofs against the committed root
+        Self::validate_merkle_proofs(input)?;
+
+        Ok(())
+    }
+
+    /// Validate that each field's Merkle proof actually reconstructs the
+    /// committed `root`, mirroring the checks the guest performs so a
+    /// tampered sibling/direction is caught here instead of only surfacing
+    /// as a guest panic during proof generation.
+    fn validate_merkle_proofs(input: &PaymentInstructionInput) -> Result<()> {
+        let suite = input.hash_suite;
+
+        let debtor_leaf = suite.leaf_hash(&input.debtor_hash, 1u8);
+        let debtor_proof = MerkleProof {
+            siblings: input.debtor_proof_siblings.clone(),
+            directions: input.debtor_proof_directions.clone(),
+        };
+        if !MerkleTree::verify_proof_with_suite(&debtor_leaf, &debtor_proof, &input.root, suite) {
+            return Err(anyhow!("Invalid debtor Merkle proof"));
+        }
+
+        let creditor_leaf = suite.leaf_hash(&input.creditor_hash, 2u8);
+        let creditor_proof = MerkleProof {
+            siblings: input.creditor_proof_siblings.clone(),
+            directions: input.creditor_proof_directions.clone(),
+        };
+        if !MerkleTree::verify_proof_with_suite(&creditor_leaf, &creditor_proof, &input.root, suite) {
+            return Err(anyhow!("Invalid creditor Merkle proof"));
+        }
+
+        let amount_bytes = input.amount_value.to_be_bytes();
+        let amount_leaf = suite.leaf_hash(&amount_bytes, 3u8);
+        let amount_proof = MerkleProof {
+            siblings: input.amount_proof_siblings.clone(),
+            directions: input.amount_proof_directions.clone(),
+        };
+        if !MerkleTree::verify_proof_with_suite(&amount_leaf, &amount_proof, &input.root, suite) {
+            return Err(anyhow!("Invalid amount Merkle proof"));
+        }
+
+        let currency_leaf = suite.leaf_hash(&input.currency_hash, 4u8);
+        let currency_proof = MerkleProof {
+            siblings: input.currency_proof_siblings.clone(),
+            directions: input.currency_proof_directions.clone(),
+        };
+        if !MerkleTree::verify_proof_with_suite(&currency_leaf, &currency_proof, &input.root, suite) {
+            return Err(anyhow!("Invalid currency Merkle proof"));
+        }
+
+        let expiry_bytes = input.expiry.to_be_bytes();
+        let expiry_leaf = suite.leaf_hash(&expiry_bytes, 5u8);
+        let expiry_proof = MerkleProof {
+            siblings: input.expiry_proof_siblings.clone(),
+            directions: input.expiry_proof_directions.clone(),
+        };
+        if !MerkleTree::verify_proof_with_suite(&expiry_leaf, &expiry_proof, &input.root, suite) {
+            return Err(anyhow!("Invalid expiry Merkle proof"));
+        }
+
         Ok(())
     }
 
@@ -94,6 +162,9 @@ impl ProofValidator {
         if output.expiry != input.expiry {
             return Err(anyhow!("Expiry mismatch in output"));
         }
+        if output.hash_suite != input.hash_suite {
+            return Err(anyhow!("Hash suite mismatch in output"));
+        }
 
         Ok(())
     }
@@ -105,7 +176,8 @@ impl ProofValidator {
         Ok(())
     }
 
-    /// Validate date format (YYYYMMDD)
+    /// Validate date format (YYYYMMDD), rejecting calendar-impossible dates
+    /// such as `20240230` and correctly handling leap years via `chrono`.
     pub fn validate_date_format(date_str: &str) -> Result<u64> {
         if date_str.len() != 8 {
             return Err(anyhow!(
@@ -114,29 +186,156 @@ impl ProofValidator {
             ));
         }
 
-        let year: u32 = date_str[0..4]
+        let date = Self::parse_execution_date(date_str)?;
+        Ok(date
+            .format("%Y%m%d")
+            .to_string()
+            .parse()
+            .expect("NaiveDate::format(\"%Y%m%d\") always yields 8 ASCII digits"))
+    }
+
+    /// Parse an execution date in either `YYYYMMDD` or `YYYY-MM-DD` form into
+    /// a `NaiveDate`, rejecting dates that don't exist on the calendar (e.g.
+    /// `2024-02-30` or `2023-02-29`) instead of just range-checking each
+    /// field independently.
+    fn parse_execution_date(date_str: &str) -> Result<NaiveDate> {
+        let digits = date_str.replace('-', "");
+        if digits.len() != 8 {
+            return Err(anyhow!(
+                "Date must be 8 digits (YYYYMMDD or YYYY-MM-DD), got: {}",
+                date_str
+            ));
+        }
+
+        let year: i32 = digits[0..4]
             .parse()
             .map_err(|_| anyhow!("Invalid year in date: {}", date_str))?;
-        let month: u32 = date_str[4..6]
+        let month: u32 = digits[4..6]
             .parse()
             .map_err(|_| anyhow!("Invalid month in date: {}", date_str))?;
-        let day: u32 = date_str[6..8]
+        let day: u32 = digits[6..8]
             .parse()
             .map_err(|_| anyhow!("Invalid day in date: {}", date_str))?;
 
-        if year < 1900 || year > 2100 {
+        if !(1900..=2100).contains(&year) {
             return Err(anyhow!("Year out of range: {}", year));
         }
-        if month < 1 || month > 12 {
-            return Err(anyhow!("Month out of range: {}", month));
+
+        NaiveDate::from_ymd_opt(year, month, day)
+            .ok_or_else(|| anyhow!("Invalid calendar date: {}", date_str))
+    }
+
+    /// Validate an IBAN against the ISO 13616 mod-97 checksum and confirm its
+    /// two-letter country prefix matches the `Ctry` field of `PstlAdr`.
+    pub fn validate_iban(iban: &str, country: &str) -> Result<()> {
+        let cleaned: String = iban
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_uppercase();
+
+        if cleaned.len() < 4 || cleaned.len() > 34 {
+            return Err(anyhow!(
+                "IBAN length must be between 4 and 34 characters, got {}: {}",
+                cleaned.len(),
+                iban
+            ));
+        }
+        if !cleaned.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(anyhow!("IBAN contains non-alphanumeric characters: {}", iban));
+        }
+
+        let iban_country = &cleaned[0..2];
+        if iban_country != country.to_uppercase() {
+            return Err(anyhow!(
+                "IBAN country prefix {} does not match PstlAdr.Ctry {}",
+                iban_country,
+                country
+            ));
+        }
+
+        // Move the first four characters to the end, then expand letters to
+        // two digits each (A=10 .. Z=35) before folding mod 97.
+        let rearranged = format!("{}{}", &cleaned[4..], &cleaned[0..4]);
+        let mut acc: u64 = 0;
+        for c in rearranged.chars() {
+            let digits: u64 = if c.is_ascii_digit() {
+                c.to_digit(10).unwrap() as u64
+            } else {
+                c as u64 - 'A' as u64 + 10
+            };
+            for digit in digits.to_string().chars() {
+                acc = (acc * 10 + digit.to_digit(10).unwrap() as u64) % 97;
+            }
         }
-        if day < 1 || day > 31 {
-            return Err(anyhow!("Day out of range: {}", day));
+
+        if acc != 1 {
+            return Err(anyhow!("IBAN checksum failed: {}", iban));
         }
 
-        date_str
-            .parse::<u64>()
-            .map_err(|_| anyhow!("Failed to parse date as number: {}", date_str))
+        Ok(())
+    }
+
+    /// Validate a BIC/SWIFT code's structure: 4-letter bank code, 2-letter
+    /// country code, 2-character location code, and an optional 3-character
+    /// branch code (8 or 11 characters total).
+    pub fn validate_bic(bic: &str) -> Result<()> {
+        let cleaned = bic.trim();
+        if cleaned.len() != 8 && cleaned.len() != 11 {
+            return Err(anyhow!(
+                "BIC must be 8 or 11 characters, got {}: {}",
+                cleaned.len(),
+                bic
+            ));
+        }
+
+        let bank_code = &cleaned[0..4];
+        let country_code = &cleaned[4..6];
+        let location_code = &cleaned[6..8];
+
+        if !bank_code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(anyhow!("BIC bank code must be alphabetic: {}", bic));
+        }
+        if !country_code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(anyhow!("BIC country code must be alphabetic: {}", bic));
+        }
+        if !location_code.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(anyhow!("BIC location code must be alphanumeric: {}", bic));
+        }
+        if cleaned.len() == 11 && !cleaned[8..11].chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(anyhow!("BIC branch code must be alphanumeric: {}", bic));
+        }
+
+        Ok(())
+    }
+
+    /// Resolve a `PaymentPlan` against the presented witnesses and today's
+    /// date (`YYYYMMDD`), only emitting the public output once every
+    /// condition on the path to a `Pay` leaf is satisfied.
+    pub fn validate_plan(
+        plan: &PaymentPlan,
+        witnesses: &[Witness],
+        today: u64,
+    ) -> Result<PaymentInstructionOutput> {
+        let input = plan.apply(witnesses, today).map_err(|unmet| {
+            anyhow!(
+                "Payment plan is not yet payable, unmet conditions: {}",
+                unmet.join(", ")
+            )
+        })?;
+
+        Self::validate_input_consistency(input)?;
+
+        Ok(PaymentInstructionOutput {
+            root: input.root,
+            debtor_hash: input.debtor_hash,
+            creditor_hash: input.creditor_hash,
+            min_amount_milli: input.min_amount_milli,
+            max_amount_milli: input.max_amount_milli,
+            currency_hash: input.currency_hash,
+            expiry: input.expiry,
+            hash_suite: input.hash_suite,
+        })
     }
 
     /// Validate amount ranges
@@ -203,6 +402,14 @@ mod tests {
         assert!(ProofValidator::validate_date_format("1899").is_err()); // Too short
     }
 
+    #[test]
+    fn test_validate_date_format_rejects_impossible_calendar_dates() {
+        assert!(ProofValidator::validate_date_format("20240229").is_ok()); // 2024 is a leap year
+        assert!(ProofValidator::validate_date_format("20230229").is_err()); // 2023 is not
+        assert!(ProofValidator::validate_date_format("20240230").is_err()); // No Feb 30th
+        assert!(ProofValidator::validate_date_format("20240431").is_err()); // April has 30 days
+    }
+
     #[test]
     fn test_validate_amount_ranges() {
         assert!(ProofValidator::validate_amount_ranges(1500, 1000, 2000).is_ok());
@@ -212,6 +419,74 @@ mod tests {
         // Min > max
     }
 
+    #[test]
+    fn test_validate_expiry_mismatch_with_execution_date() {
+        let mut input = MockData::simple_valid_input();
+        input.expiry = 20250101; // Disagrees with execution_date "2024-12-31"
+
+        let result = ProofValidator::validate_input_consistency(&input);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Expiry mismatch"));
+    }
+
+    #[test]
+    fn test_validate_invalid_merkle_proof() {
+        let mut input = MockData::simple_valid_input();
+        input.debtor_proof_siblings = vec![[0xffu8; 32]]; // Bogus sibling
+        input.debtor_proof_directions = vec![0];
+
+        let result = ProofValidator::validate_input_consistency(&input);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid debtor Merkle proof"));
+    }
+
+    #[test]
+    fn test_validate_iban_checksum() {
+        assert!(ProofValidator::validate_iban("DE89 3704 0044 0532 0130 00", "DE").is_ok());
+        assert!(ProofValidator::validate_iban("GB00WEST12345698765432", "GB").is_err()); // Bad checksum
+        assert!(ProofValidator::validate_iban("DE89370400440532013000", "FR").is_err()); // Country mismatch
+        assert!(ProofValidator::validate_iban("AB", "AB").is_err()); // Too short
+    }
+
+    #[test]
+    fn test_validate_iban_with_mock_invalid_fixture() {
+        let input = MockData::invalid_iban_input();
+        assert!(input.debtor_data.contains("GB00WEST12345698765432"));
+        assert!(ProofValidator::validate_iban("GB00WEST12345698765432", "GB").is_err());
+    }
+
+    #[test]
+    fn test_validate_bic_structure() {
+        assert!(ProofValidator::validate_bic("DEUTDEFF").is_ok()); // 8-char
+        assert!(ProofValidator::validate_bic("DEUTDEFF500").is_ok()); // 11-char with branch
+        assert!(ProofValidator::validate_bic("DEUT1FF").is_err()); // Wrong length
+        assert!(ProofValidator::validate_bic("1EUTDEFF").is_err()); // Numeric bank code
+    }
+
+    #[test]
+    fn test_validate_plan_emits_output_once_payable() {
+        use crate::payment_plan::PaymentPlan;
+
+        let plan = PaymentPlan::after(
+            Witness::Signature("escrow-agent".to_string()),
+            PaymentPlan::pay(MockData::simple_valid_input()),
+        );
+
+        let unmet = ProofValidator::validate_plan(&plan, &[], 20240101);
+        assert!(unmet.is_err());
+        assert!(unmet
+            .unwrap_err()
+            .to_string()
+            .contains("Payment plan is not yet payable"));
+
+        let witnesses = [Witness::Signature("escrow-agent".to_string())];
+        let output = ProofValidator::validate_plan(&plan, &witnesses, 20240101).unwrap();
+        assert_eq!(output.root, MockData::simple_valid_input().root);
+    }
+
     #[test]
     fn test_validate_output_consistency() {
         let input = MockData::simple_valid_input();
@@ -0,0 +1,353 @@
+//! Pluggable proving backend selection. Following the `Factory::new(vm_type)`
+//! pattern used to abstract over multiple VM implementations chosen at
+//! runtime, `ProverBackendFactory::create` hands back a `ProverBackend` trait
+//! object so callers (the CLI, batch tests, stress tests) dispatch through
+//! one interface regardless of whether the proof is produced by the local
+//! RISC Zero prover, a remote Bonsai-style proving service, or a dev/mock
+//! backend that skips real proving entirely.
+
+use crate::payment_instruction_generator::{PaymentInstructionInput, PaymentInstructionOutput};
+use crate::test_helpers::{generate_and_verify_proof, TestMetrics};
+use crate::{TestConfig, TestResult};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Which `ProverBackend` implementation `ProverBackendFactory::create` hands
+/// back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum BackendKind {
+    Local,
+    Remote,
+    Mock,
+}
+
+impl Default for BackendKind {
+    fn default() -> Self {
+        BackendKind::Local
+    }
+}
+
+impl std::str::FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "local" => Ok(BackendKind::Local),
+            "remote" => Ok(BackendKind::Remote),
+            "mock" => Ok(BackendKind::Mock),
+            other => Err(format!(
+                "unknown prover backend '{}': expected local, remote, or mock",
+                other
+            )),
+        }
+    }
+}
+
+/// Abstracts over where a proof is actually produced, so a call site works
+/// unchanged whether it dispatches to the local prover, a remote one, or a
+/// mock that skips proving entirely.
+pub trait ProverBackend {
+    fn generate_and_verify(
+        &self,
+        input: &PaymentInstructionInput,
+        config: &TestConfig,
+    ) -> TestResult<(PaymentInstructionOutput, TestMetrics)>;
+
+    /// Which `BackendKind` this implementation is, so a cross-backend test
+    /// can label its assertions without re-deriving the mapping from the
+    /// concrete type.
+    fn id(&self) -> BackendKind;
+}
+
+/// Proves and verifies with the local RISC Zero prover, exactly as
+/// `generate_and_verify_proof` already does.
+pub struct LocalProverBackend;
+
+impl ProverBackend for LocalProverBackend {
+    fn generate_and_verify(
+        &self,
+        input: &PaymentInstructionInput,
+        config: &TestConfig,
+    ) -> TestResult<(PaymentInstructionOutput, TestMetrics)> {
+        generate_and_verify_proof(input, config)
+    }
+
+    fn id(&self) -> BackendKind {
+        BackendKind::Local
+    }
+}
+
+/// Submits proving work to a remote Bonsai-style proving service instead of
+/// tying up the caller's own CPU on a heavy local proof.
+pub struct RemoteProverBackend {
+    pub endpoint: String,
+}
+
+impl RemoteProverBackend {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+        }
+    }
+}
+
+impl ProverBackend for RemoteProverBackend {
+    fn generate_and_verify(
+        &self,
+        _input: &PaymentInstructionInput,
+        _config: &TestConfig,
+    ) -> TestResult<(PaymentInstructionOutput, TestMetrics)> {
+        // A real deployment submits `input` to `self.endpoint` (a
+        // Bonsai-style proving service), polls until the remote session
+        // completes, and downloads the resulting receipt rather than
+        // blocking this thread on local proving. No such service is
+        // reachable from this test harness, so surface that plainly instead
+        // of silently falling back to local proving.
+        Err(anyhow::anyhow!(
+            "remote prover backend at '{}' is not reachable from this environment",
+            self.endpoint
+        ))
+    }
+
+    fn id(&self) -> BackendKind {
+        BackendKind::Remote
+    }
+}
+
+/// Skips real proving entirely: derives the output directly from the
+/// input's own public fields and reports zeroed-out metrics. Lets a fast CI
+/// path exercise the pipeline's shape without paying for a real RISC Zero
+/// proof.
+pub struct MockProverBackend;
+
+impl ProverBackend for MockProverBackend {
+    fn generate_and_verify(
+        &self,
+        input: &PaymentInstructionInput,
+        _config: &TestConfig,
+    ) -> TestResult<(PaymentInstructionOutput, TestMetrics)> {
+        let output = PaymentInstructionOutput {
+            root: input.root,
+            debtor_hash: input.debtor_hash,
+            creditor_hash: input.creditor_hash,
+            min_amount_milli: input.min_amount_milli,
+            max_amount_milli: input.max_amount_milli,
+            currency_hash: input.currency_hash,
+            expiry: input.expiry,
+            hash_suite: input.hash_suite,
+        };
+
+        let metrics = TestMetrics {
+            proof_generation_time: Duration::ZERO,
+            verification_time: Duration::ZERO,
+            memory_usage_mb: 0,
+            proof_size_bytes: 0,
+            journal_size_bytes: 0,
+            total_cycles: 0,
+            user_cycles: 0,
+            segments: 0,
+            paging_cycles: 0,
+            cycle_limit: 0,
+            mapped_artifact_mb: 0,
+            attempt_count: 1,
+            verification_gas: 0,
+            cache_hit: false,
+        };
+
+        Ok((output, metrics))
+    }
+
+    fn id(&self) -> BackendKind {
+        BackendKind::Mock
+    }
+}
+
+/// Hands back the `ProverBackend` implementation for a `BackendKind`,
+/// mirroring the `Factory::new(vm_type)` pattern used to select among
+/// several VM implementations at runtime.
+pub struct ProverBackendFactory;
+
+impl ProverBackendFactory {
+    pub fn create(kind: BackendKind) -> Box<dyn ProverBackend> {
+        match kind {
+            BackendKind::Local => Box::new(LocalProverBackend),
+            BackendKind::Remote => Box::new(RemoteProverBackend::new(
+                std::env::var("BONSAI_API_URL").unwrap_or_else(|_| "https://api.bonsai.xyz".to_string()),
+            )),
+            BackendKind::Mock => Box::new(MockProverBackend),
+        }
+    }
+
+    /// Every registered backend, for a caller (e.g. `phase3_run_all`) that
+    /// wants to exercise the same pipeline against each implementation in
+    /// turn rather than picking just one `BackendKind` up front.
+    pub fn all() -> Vec<Box<dyn ProverBackend>> {
+        vec![
+            Box::new(LocalProverBackend),
+            Box::new(RemoteProverBackend::new(
+                std::env::var("BONSAI_API_URL").unwrap_or_else(|_| "https://api.bonsai.xyz".to_string()),
+            )),
+            Box::new(MockProverBackend),
+        ]
+    }
+}
+
+/// Run `input` through every backend in `backends` and assert they commit
+/// the same `root`/`debtor_hash`/`creditor_hash` journal fields, so adding a
+/// second real proving backend (SP1, a different zkVM) alongside RISC Zero
+/// can be caught diverging from it immediately instead of only at
+/// deployment. Backends that fail outright (e.g. `RemoteProverBackend`
+/// with no reachable service) are skipped rather than failing the
+/// comparison — this checks agreement among backends that actually ran,
+/// not that every registered backend is currently reachable.
+pub fn assert_backends_agree_on_output(
+    backends: &[Box<dyn ProverBackend>],
+    input: &PaymentInstructionInput,
+    config: &TestConfig,
+) -> TestResult<()> {
+    let mut reference: Option<(BackendKind, PaymentInstructionOutput)> = None;
+
+    for backend in backends {
+        let (output, _metrics) = match backend.generate_and_verify(input, config) {
+            Ok(result) => result,
+            Err(_) => continue,
+        };
+
+        match &reference {
+            None => reference = Some((backend.id(), output)),
+            Some((reference_kind, reference_output)) => {
+                if output.root != reference_output.root
+                    || output.debtor_hash != reference_output.debtor_hash
+                    || output.creditor_hash != reference_output.creditor_hash
+                {
+                    return Err(anyhow::anyhow!(
+                        "backend {:?} disagrees with reference backend {:?} on committed output",
+                        backend.id(),
+                        reference_kind
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_data::MockData;
+
+    #[test]
+    fn test_backend_kind_from_str_accepts_known_values() {
+        assert_eq!("local".parse::<BackendKind>().unwrap(), BackendKind::Local);
+        assert_eq!("Remote".parse::<BackendKind>().unwrap(), BackendKind::Remote);
+        assert_eq!("MOCK".parse::<BackendKind>().unwrap(), BackendKind::Mock);
+        assert!("bogus".parse::<BackendKind>().is_err());
+    }
+
+    #[test]
+    fn test_backend_kind_default_is_local() {
+        assert_eq!(BackendKind::default(), BackendKind::Local);
+    }
+
+    #[test]
+    fn test_mock_backend_echoes_public_fields_without_proving() {
+        let input = MockData::simple_valid_input();
+        let config = TestConfig::default();
+
+        let (output, metrics) = MockProverBackend.generate_and_verify(&input, &config).unwrap();
+
+        assert_eq!(output.root, input.root);
+        assert_eq!(output.debtor_hash, input.debtor_hash);
+        assert_eq!(output.expiry, input.expiry);
+        assert_eq!(metrics.attempt_count, 1);
+        assert_eq!(metrics.proof_size_bytes, 0);
+    }
+
+    #[test]
+    fn test_remote_backend_reports_its_endpoint_when_unreachable() {
+        let input = MockData::simple_valid_input();
+        let config = TestConfig::default();
+        let backend = RemoteProverBackend::new("https://bonsai.example.invalid");
+
+        let error = backend.generate_and_verify(&input, &config).unwrap_err();
+        assert!(error.to_string().contains("bonsai.example.invalid"));
+    }
+
+    #[test]
+    fn test_factory_creates_matching_backend_kind() {
+        let mock = ProverBackendFactory::create(BackendKind::Mock);
+        let input = MockData::simple_valid_input();
+        let config = TestConfig::default();
+        assert!(mock.generate_and_verify(&input, &config).is_ok());
+
+        let remote = ProverBackendFactory::create(BackendKind::Remote);
+        assert!(remote.generate_and_verify(&input, &config).is_err());
+    }
+
+    #[test]
+    fn test_each_backend_reports_its_own_id() {
+        assert_eq!(LocalProverBackend.id(), BackendKind::Local);
+        assert_eq!(MockProverBackend.id(), BackendKind::Mock);
+        assert_eq!(
+            RemoteProverBackend::new("https://bonsai.example.invalid").id(),
+            BackendKind::Remote
+        );
+    }
+
+    #[test]
+    fn test_factory_all_registers_every_backend_kind() {
+        let backends = ProverBackendFactory::all();
+        let ids: Vec<BackendKind> = backends.iter().map(|backend| backend.id()).collect();
+        assert!(ids.contains(&BackendKind::Local));
+        assert!(ids.contains(&BackendKind::Remote));
+        assert!(ids.contains(&BackendKind::Mock));
+    }
+
+    #[test]
+    fn test_assert_backends_agree_skips_unreachable_backends_without_failing() {
+        let input = MockData::simple_valid_input();
+        let config = TestConfig::default();
+
+        // Only the mock backend can actually run here (no local prover or
+        // reachable Bonsai endpoint in this environment), so the unreachable
+        // remote backend is skipped and agreement among the backends that
+        // ran holds rather than erroring.
+        let backends: Vec<Box<dyn ProverBackend>> = vec![
+            Box::new(MockProverBackend),
+            Box::new(RemoteProverBackend::new("https://bonsai.example.invalid")),
+        ];
+        assert!(assert_backends_agree_on_output(&backends, &input, &config).is_ok());
+    }
+
+    #[test]
+    fn test_assert_backends_agree_detects_disagreement() {
+        struct DisagreeingBackend;
+        impl ProverBackend for DisagreeingBackend {
+            fn generate_and_verify(
+                &self,
+                input: &PaymentInstructionInput,
+                _config: &TestConfig,
+            ) -> TestResult<(PaymentInstructionOutput, TestMetrics)> {
+                let mut output = MockProverBackend
+                    .generate_and_verify(input, &TestConfig::default())?
+                    .0;
+                output.root = [0xFFu8; 32];
+                Ok((output, MockProverBackend.generate_and_verify(input, &TestConfig::default())?.1))
+            }
+
+            fn id(&self) -> BackendKind {
+                BackendKind::Remote
+            }
+        }
+
+        let input = MockData::simple_valid_input();
+        let config = TestConfig::default();
+        let backends: Vec<Box<dyn ProverBackend>> =
+            vec![Box::new(MockProverBackend), Box::new(DisagreeingBackend)];
+
+        let error = assert_backends_agree_on_output(&backends, &input, &config).unwrap_err();
+        assert!(error.to_string().contains("disagrees"));
+    }
+}
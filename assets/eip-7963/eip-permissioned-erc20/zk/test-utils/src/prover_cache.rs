@@ -0,0 +1,332 @@
+//! A disk-backed, memory-mapped cache for large, reusable proving artifacts
+//! (the guest ELF image, keccak/segment lookup tables, precomputed
+//! Merkle-path scratch) so the OS pages them in on demand instead of the
+//! harness holding them resident on the heap for every proof.
+
+use crate::crypto_utils::keccak256;
+use memmap2::Mmap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// A read-only memory-mapped proving artifact, keyed by the keccak256
+/// content hash of the bytes it was built from.
+pub struct MappedArtifact {
+    key: [u8; 32],
+    mmap: Mmap,
+}
+
+impl MappedArtifact {
+    pub fn key(&self) -> [u8; 32] {
+        self.key
+    }
+
+    /// The artifact's bytes, paged in from disk by the OS on demand rather
+    /// than held resident on the heap.
+    pub fn bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Size of the mapping. These bytes are backed by the OS page cache,
+    /// not the heap, so they are not "resident" in the sense `TestMetrics`'s
+    /// heap-based `memory_usage_mb` measures.
+    pub fn mapped_bytes(&self) -> u64 {
+        self.mmap.len() as u64
+    }
+}
+
+/// Lazily builds a disk-backed file per distinct artifact on first use and
+/// mmaps it read-only, keeping the mapping around so callers that share one
+/// `ProverCache` (e.g. every item in a `generate_batch` loop) reuse the same
+/// mapping rather than re-allocating per item.
+pub struct ProverCache {
+    cache_dir: PathBuf,
+    mapped: Vec<Arc<MappedArtifact>>,
+}
+
+impl ProverCache {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            mapped: Vec::new(),
+        }
+    }
+
+    /// Return the mapped artifact for `bytes`, building its cache file on
+    /// first use and reusing the existing in-memory mapping on every
+    /// subsequent call with the same content.
+    pub fn get_or_insert(&mut self, bytes: &[u8]) -> io::Result<Arc<MappedArtifact>> {
+        let key = keccak256(bytes);
+        if let Some(existing) = self.mapped.iter().find(|artifact| artifact.key == key) {
+            return Ok(existing.clone());
+        }
+
+        fs::create_dir_all(&self.cache_dir)?;
+        let path = self.artifact_path(&key);
+        if !path.exists() {
+            let mut file = File::create(&path)?;
+            file.write_all(bytes)?;
+            file.flush()?;
+        }
+
+        let file = File::open(&path)?;
+        // SAFETY: the cache file lives under this process's own cache
+        // directory, is written once up front, and is never mutated again,
+        // so the mapping cannot observe a concurrent write.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let artifact = Arc::new(MappedArtifact { key, mmap });
+        self.mapped.push(artifact.clone());
+        Ok(artifact)
+    }
+
+    fn artifact_path(&self, key: &[u8; 32]) -> PathBuf {
+        let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        self.cache_dir.join(format!("{}.bin", hex))
+    }
+
+    /// Total bytes mapped across every artifact this cache holds, for
+    /// reporting separately from heap-based memory usage.
+    pub fn mapped_bytes(&self) -> u64 {
+        self.mapped.iter().map(|artifact| artifact.mapped_bytes()).sum()
+    }
+
+    /// Number of distinct artifacts mapped so far.
+    pub fn len(&self) -> usize {
+        self.mapped.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mapped.is_empty()
+    }
+}
+
+/// Content-addressed, disk-backed cache of serialized proof receipts, keyed
+/// by the keccak256 hash of `(guest image ID, canonicalized input)` so
+/// repeated batch/stress runs over the same input read a previously
+/// generated receipt back instead of re-proving. Distinct from
+/// `ProverCache` above, which maps large proving *input* artifacts (the
+/// guest ELF); this caches the proving *output*. Entries are read with a
+/// checksum check to detect on-disk corruption, and `put` evicts the
+/// oldest entries (by file modified time) once the cache directory grows
+/// past `max_size_bytes`, so stress batches of hundreds of distinct inputs
+/// stay bounded on disk.
+pub struct ReceiptCache {
+    cache_dir: PathBuf,
+    max_size_bytes: u64,
+}
+
+impl ReceiptCache {
+    pub fn new(cache_dir: impl Into<PathBuf>, max_size_bytes: u64) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+            max_size_bytes,
+        }
+    }
+
+    /// Derive this cache's key from the guest `image_id` and the
+    /// canonicalized (e.g. bincode-serialized) bytes of the input a
+    /// receipt was proven against.
+    pub fn key_for(image_id: &[u32; 8], canonicalized_input: &[u8]) -> [u8; 32] {
+        let mut preimage = Vec::with_capacity(32 + canonicalized_input.len());
+        for word in image_id {
+            preimage.extend_from_slice(&word.to_be_bytes());
+        }
+        preimage.extend_from_slice(canonicalized_input);
+        keccak256(&preimage)
+    }
+
+    /// Look up a previously cached receipt's raw serialized bytes. Returns
+    /// `Ok(None)` on a cache miss, and an `Err` if a stored entry's
+    /// trailing checksum doesn't match its contents (on-disk corruption).
+    pub fn get(&self, key: &[u8; 32]) -> io::Result<Option<Vec<u8>>> {
+        let path = self.entry_path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let mut stored = fs::read(&path)?;
+        if stored.len() < 32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("cache entry {} is too short to contain a checksum", hex_key(key)),
+            ));
+        }
+
+        let checksum_offset = stored.len() - 32;
+        let expected_checksum: [u8; 32] = stored[checksum_offset..]
+            .try_into()
+            .expect("slice of length 32 always converts to [u8; 32]");
+        stored.truncate(checksum_offset);
+
+        if keccak256(&stored) != expected_checksum {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("cache entry {} failed checksum verification", hex_key(key)),
+            ));
+        }
+
+        Ok(Some(stored))
+    }
+
+    /// Store `bytes` under `key` with an appended keccak256 checksum, then
+    /// evict the oldest entries until the cache directory's total size is
+    /// back at or under `max_size_bytes`.
+    pub fn put(&self, key: &[u8; 32], bytes: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.cache_dir)?;
+
+        let checksum = keccak256(bytes);
+        let mut stored = Vec::with_capacity(bytes.len() + 32);
+        stored.extend_from_slice(bytes);
+        stored.extend_from_slice(&checksum);
+
+        fs::write(self.entry_path(key), &stored)?;
+        self.evict_to_bound()
+    }
+
+    fn entry_path(&self, key: &[u8; 32]) -> PathBuf {
+        self.cache_dir.join(format!("{}.receipt", hex_key(key)))
+    }
+
+    /// Remove the oldest entries (by file modified time) until the cache
+    /// directory's total size is at or under `max_size_bytes`.
+    fn evict_to_bound(&self) -> io::Result<()> {
+        let mut entries: Vec<(PathBuf, u64, std::time::SystemTime)> = fs::read_dir(&self.cache_dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let modified = metadata.modified().ok()?;
+                Some((entry.path(), metadata.len(), modified))
+            })
+            .collect();
+
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_size_bytes {
+            return Ok(());
+        }
+
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in entries {
+            if total <= self.max_size_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total = total.saturating_sub(len);
+        }
+
+        Ok(())
+    }
+}
+
+fn hex_key(key: &[u8; 32]) -> String {
+    key.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("test-utils-prover-cache-test-{}", name))
+    }
+
+    #[test]
+    fn test_get_or_insert_reuses_mapping_for_same_content() {
+        let dir = temp_cache_dir("reuse");
+        let mut cache = ProverCache::new(&dir);
+
+        let artifact_a = cache.get_or_insert(b"guest elf bytes").unwrap();
+        let artifact_b = cache.get_or_insert(b"guest elf bytes").unwrap();
+
+        assert_eq!(cache.len(), 1, "identical content must share one mapping");
+        assert_eq!(artifact_a.key(), artifact_b.key());
+        assert_eq!(artifact_a.bytes(), b"guest elf bytes");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_get_or_insert_maps_distinct_content_separately() {
+        let dir = temp_cache_dir("distinct");
+        let mut cache = ProverCache::new(&dir);
+
+        cache.get_or_insert(b"lookup table one").unwrap();
+        cache.get_or_insert(b"lookup table two").unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.mapped_bytes(),
+            "lookup table one".len() as u64 + "lookup table two".len() as u64
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_receipt_cache_round_trips_a_stored_entry() {
+        let dir = temp_cache_dir("receipt-round-trip");
+        let cache = ReceiptCache::new(&dir, 1024 * 1024);
+        let key = ReceiptCache::key_for(&[1, 2, 3, 4, 5, 6, 7, 8], b"canonical input bytes");
+
+        assert!(cache.get(&key).unwrap().is_none());
+
+        cache.put(&key, b"serialized receipt bytes").unwrap();
+        let retrieved = cache.get(&key).unwrap().unwrap();
+        assert_eq!(retrieved, b"serialized receipt bytes");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_receipt_cache_key_for_differs_on_image_id_or_input() {
+        let key_a = ReceiptCache::key_for(&[1; 8], b"input a");
+        let key_b = ReceiptCache::key_for(&[2; 8], b"input a");
+        let key_c = ReceiptCache::key_for(&[1; 8], b"input b");
+
+        assert_ne!(key_a, key_b);
+        assert_ne!(key_a, key_c);
+    }
+
+    #[test]
+    fn test_receipt_cache_get_detects_corrupted_checksum() {
+        let dir = temp_cache_dir("receipt-corruption");
+        let cache = ReceiptCache::new(&dir, 1024 * 1024);
+        let key = ReceiptCache::key_for(&[9; 8], b"some input");
+
+        cache.put(&key, b"original bytes").unwrap();
+
+        // Tamper with the stored entry's payload, leaving the checksum stale.
+        let path = cache.entry_path(&key);
+        let mut stored = fs::read(&path).unwrap();
+        stored[0] ^= 0xFF;
+        fs::write(&path, &stored).unwrap();
+
+        let error = cache.get(&key).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_receipt_cache_put_evicts_oldest_entries_past_max_size() {
+        let dir = temp_cache_dir("receipt-eviction");
+        // Each stored entry is 10 payload bytes + 32 checksum bytes = 42
+        // bytes; bound the cache to fit only one.
+        let cache = ReceiptCache::new(&dir, 42);
+
+        let key_a = ReceiptCache::key_for(&[1; 8], b"input-a-1");
+        let key_b = ReceiptCache::key_for(&[1; 8], b"input-b-1");
+
+        cache.put(&key_a, b"0123456789").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache.put(&key_b, b"9876543210").unwrap();
+
+        assert!(
+            cache.get(&key_a).unwrap().is_none(),
+            "oldest entry should have been evicted"
+        );
+        assert!(cache.get(&key_b).unwrap().is_some());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
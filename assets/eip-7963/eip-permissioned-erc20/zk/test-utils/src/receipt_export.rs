@@ -0,0 +1,202 @@
+//! Machine-readable export of a generated proof, so a downstream on-chain
+//! settlement contract can consume it without re-deriving RISC Zero's
+//! receipt layout by hand, following the same "emit a structured JSON
+//! bundle for downstream consumption" shape used by other proving
+//! pipelines' spend/output export flows.
+
+use crate::crypto_utils::bytes_to_hex;
+use crate::crypto_utils::HashSuite;
+use crate::payment_instruction_generator::PaymentInstructionOutput;
+use crate::test_helpers::TestMetrics;
+use crate::{TestResult, METHOD_ID};
+use risc0_zkvm::Receipt;
+use serde::Serialize;
+
+/// A self-contained, JSON-serializable bundle of everything a downstream
+/// verifier needs to check a proof: the seal bytes, the committed public
+/// journal fields, the image ID the seal was proven against, and the run's
+/// performance metrics.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceiptBundle {
+    /// Hex-encoded Groth16 seal when the receipt is a Groth16 receipt,
+    /// otherwise the full bincode-serialized receipt (composite and
+    /// succinct receipts have no single flat "seal" field).
+    pub seal_hex: String,
+    pub image_id_hex: String,
+    pub journal: ExportedJournal,
+    pub metrics: ExportedMetrics,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedJournal {
+    pub root: String,
+    pub debtor_hash: String,
+    pub creditor_hash: String,
+    pub min_amount_milli: u64,
+    pub max_amount_milli: u64,
+    pub currency_hash: String,
+    pub expiry: u64,
+    pub hash_suite_id: u8,
+}
+
+impl From<&PaymentInstructionOutput> for ExportedJournal {
+    fn from(output: &PaymentInstructionOutput) -> Self {
+        Self {
+            root: bytes_to_hex(&output.root),
+            debtor_hash: bytes_to_hex(&output.debtor_hash),
+            creditor_hash: bytes_to_hex(&output.creditor_hash),
+            min_amount_milli: output.min_amount_milli,
+            max_amount_milli: output.max_amount_milli,
+            currency_hash: bytes_to_hex(&output.currency_hash),
+            expiry: output.expiry,
+            hash_suite_id: output.hash_suite.id(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedMetrics {
+    pub proof_generation_ms: u128,
+    pub verification_ms: u128,
+    pub proof_size_bytes: usize,
+    pub journal_size_bytes: usize,
+    pub total_cycles: u64,
+    pub attempt_count: u32,
+}
+
+impl From<&TestMetrics> for ExportedMetrics {
+    fn from(metrics: &TestMetrics) -> Self {
+        Self {
+            proof_generation_ms: metrics.proof_generation_time.as_millis(),
+            verification_ms: metrics.verification_time.as_millis(),
+            proof_size_bytes: metrics.proof_size_bytes,
+            journal_size_bytes: metrics.journal_size_bytes,
+            total_cycles: metrics.total_cycles,
+            attempt_count: metrics.attempt_count,
+        }
+    }
+}
+
+impl ReceiptBundle {
+    pub fn new(
+        receipt: &Receipt,
+        output: &PaymentInstructionOutput,
+        metrics: &TestMetrics,
+    ) -> TestResult<Self> {
+        Ok(Self {
+            seal_hex: extract_seal_hex(receipt)?,
+            image_id_hex: method_id_hex(),
+            journal: ExportedJournal::from(output),
+            metrics: ExportedMetrics::from(metrics),
+        })
+    }
+}
+
+/// Hex-encode the receipt's Groth16 seal when one exists, since that's the
+/// exact calldata an on-chain verifier consumes; fall back to the full
+/// serialized receipt for composite/succinct receipts, which have no
+/// equivalent flat seal.
+fn extract_seal_hex(receipt: &Receipt) -> TestResult<String> {
+    match receipt.inner.groth16() {
+        Ok(groth16) => Ok(bytes_to_hex(&groth16.seal)),
+        Err(_) => {
+            let bytes = bincode::serialize(receipt)?;
+            Ok(bytes_to_hex(&bytes))
+        }
+    }
+}
+
+/// Render `METHOD_ID` (a `[u32; 8]` digest) as the big-endian `bytes32` hex
+/// string a Solidity verifier would compare an image ID against.
+fn method_id_hex() -> String {
+    let mut bytes = Vec::with_capacity(32);
+    for word in METHOD_ID {
+        bytes.extend_from_slice(&word.to_be_bytes());
+    }
+    bytes_to_hex(&bytes)
+}
+
+/// Fixed Solidity ABI layout of the payment-instruction guest's journal,
+/// matching `PaymentInstructionOutput`'s field order, so an on-chain
+/// settlement contract can `abi.decode` it without re-deriving the layout
+/// from the Rust struct.
+pub const JOURNAL_ABI_TYPES: [&str; 8] = [
+    "bytes32", "bytes32", "bytes32", "uint64", "uint64", "bytes32", "uint64", "uint8",
+];
+
+pub const JOURNAL_FIELD_NAMES: [&str; 8] = [
+    "root",
+    "debtorHash",
+    "creditorHash",
+    "minAmountMilli",
+    "maxAmountMilli",
+    "currencyHash",
+    "expiry",
+    "hashSuite",
+];
+
+/// Everything an on-chain settlement contract needs to verify a Groth16
+/// receipt produced by this crate without re-deriving it by hand: the guest
+/// image ID the seal was proven against and the fixed ABI layout of its
+/// journal. The Groth16 verifying key itself is not exported here — it is
+/// baked into the deployed `RiscZeroGroth16Verifier` contract, not into any
+/// individual receipt.
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifierExport {
+    pub image_id_hex: String,
+    pub journal_abi_types: Vec<String>,
+    pub journal_field_names: Vec<String>,
+}
+
+impl VerifierExport {
+    pub fn for_payment_instruction_guest() -> Self {
+        Self {
+            image_id_hex: method_id_hex(),
+            journal_abi_types: JOURNAL_ABI_TYPES.iter().map(|s| s.to_string()).collect(),
+            journal_field_names: JOURNAL_FIELD_NAMES.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_output() -> PaymentInstructionOutput {
+        PaymentInstructionOutput {
+            root: [1u8; 32],
+            debtor_hash: [2u8; 32],
+            creditor_hash: [3u8; 32],
+            min_amount_milli: 1_000,
+            max_amount_milli: 5_000,
+            currency_hash: [4u8; 32],
+            expiry: 20_300_101,
+            hash_suite: HashSuite::default(),
+        }
+    }
+
+    #[test]
+    fn test_exported_journal_hex_encodes_hash_fields() {
+        let journal = ExportedJournal::from(&sample_output());
+        assert_eq!(journal.root, format!("0x{}", "01".repeat(32)));
+        assert_eq!(journal.min_amount_milli, 1_000);
+        assert_eq!(journal.expiry, 20_300_101);
+    }
+
+    #[test]
+    fn test_verifier_export_layout_matches_journal_field_count() {
+        let export = VerifierExport::for_payment_instruction_guest();
+        assert_eq!(export.journal_abi_types.len(), export.journal_field_names.len());
+        assert_eq!(export.journal_abi_types.len(), 8);
+        assert_eq!(export.journal_abi_types[0], "bytes32");
+        assert_eq!(export.journal_field_names[1], "debtorHash");
+    }
+
+    #[test]
+    fn test_method_id_hex_is_32_bytes() {
+        let hex = method_id_hex();
+        // "0x" + 64 hex chars for 32 bytes.
+        assert_eq!(hex.len(), 66);
+        assert!(hex.starts_with("0x"));
+    }
+}
@@ -60,6 +60,7 @@ fn phase2_basic_proof_generation() {
                 max_verify_time: Duration::from_secs(1),
                 max_proof_size: 10 * 1024 * 1024, // 10MB
                 max_memory_mb: 4096,
+                max_cycles: 10_000_000,
             };
 
             assert_performance_requirements(&metrics, &requirements);
@@ -333,6 +334,7 @@ fn phase2_performance_stress_test() {
                 max_verify_time: Duration::from_millis(500), // 500ms
                 max_proof_size: 5 * 1024 * 1024,             // 5MB
                 max_memory_mb: 2048,                         // 2GB
+                max_cycles: 4_000_000,
             };
 
             assert_performance_requirements(&metrics, &strict_requirements);
@@ -1,7 +1,87 @@
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-/// Compute Keccak256 hash using SHA256 as a substitute (matching guest implementation)
-pub fn keccak256(data: &[u8]) -> [u8; 32] {
+/// Which hash primitives a payment instruction was committed under. The
+/// suite identifier is committed into the journal alongside the fields it
+/// protects, so a verifier knows which primitive to recompute against
+/// instead of assuming a fixed Poseidon+keccak pairing — mirroring acmed's
+/// `key_type.rs`/`openssl_hash.rs` pattern of making the algorithm a
+/// first-class selectable enum rather than a hardcoded call.
+///
+/// `poseidon_hash` now delegates to the real Poseidon sponge in
+/// [`crate::poseidon`], so `PoseidonKeccak`/`PoseidonSha256`'s node hashing
+/// is genuinely ZK-friendly rather than the SHA256 placeholder both suites
+/// used to share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, arbitrary::Arbitrary)]
+pub enum HashSuite {
+    /// Poseidon for internal tree nodes, keccak256 for field commitments
+    /// (the suite every caller used before this was configurable).
+    PoseidonKeccak,
+    /// Poseidon for internal tree nodes, plain SHA256 for field commitments.
+    PoseidonSha256,
+    /// keccak256 end-to-end, for issuers with no Poseidon support.
+    KeccakOnly,
+}
+
+impl Default for HashSuite {
+    fn default() -> Self {
+        HashSuite::PoseidonKeccak
+    }
+}
+
+impl HashSuite {
+    /// Stable identifier committed into the journal.
+    pub fn id(&self) -> u8 {
+        match self {
+            HashSuite::PoseidonKeccak => 0,
+            HashSuite::PoseidonSha256 => 1,
+            HashSuite::KeccakOnly => 2,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Result<Self, String> {
+        match id {
+            0 => Ok(HashSuite::PoseidonKeccak),
+            1 => Ok(HashSuite::PoseidonSha256),
+            2 => Ok(HashSuite::KeccakOnly),
+            other => Err(format!("unknown hash suite id: {}", other)),
+        }
+    }
+
+    /// Hash a single field (debtor/creditor/currency data) into its public
+    /// commitment.
+    pub fn field_hash(&self, data: &[u8]) -> [u8; 32] {
+        match self {
+            HashSuite::PoseidonKeccak | HashSuite::KeccakOnly => keccak256(data),
+            HashSuite::PoseidonSha256 => sha256_hash(data),
+        }
+    }
+
+    /// Hash a tagged leaf preimage for the Merkle tree.
+    pub fn leaf_hash(&self, preimage: &[u8], tag: u8) -> [u8; 32] {
+        match self {
+            HashSuite::PoseidonKeccak | HashSuite::PoseidonSha256 => {
+                compute_leaf_hash(preimage, tag)
+            }
+            HashSuite::KeccakOnly => {
+                let mut tagged = preimage.to_vec();
+                tagged.push(tag);
+                keccak256(&tagged)
+            }
+        }
+    }
+
+    /// Hash two child nodes into their parent.
+    pub fn node_hash(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        match self {
+            HashSuite::PoseidonKeccak | HashSuite::PoseidonSha256 => poseidon_hash(left, right),
+            HashSuite::KeccakOnly => crate::hashing::keccak256_node_hash(left, right),
+        }
+    }
+}
+
+/// Plain SHA256 of a field (see `HashSuite::PoseidonSha256`).
+pub fn sha256_hash(data: &[u8]) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(data);
     let result = hasher.finalize();
@@ -10,18 +90,150 @@ pub fn keccak256(data: &[u8]) -> [u8; 32] {
     output
 }
 
-/// Simple Poseidon-like hash function using SHA256 for compatibility (matching guest implementation)
+/// Genuine Keccak-256, matching the EVM's `keccak256` opcode and the
+/// guest's copy byte-for-byte. Delegates to [`crate::hashing`], the single
+/// shared definition both the host and guest are kept in lockstep with.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    crate::hashing::keccak256(data)
+}
+
+/// Combine two Merkle child nodes under the real Poseidon sponge in
+/// [`crate::poseidon`], replacing the SHA256 placeholder this function used
+/// prior to `HashSuite::PoseidonKeccak`/`PoseidonSha256` gaining a real
+/// ZK-friendly node-combining step.
 pub fn poseidon_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update(left);
-    hasher.update(right);
-    let result = hasher.finalize();
-    let mut output = [0u8; 32];
-    output.copy_from_slice(&result);
-    output
+    crate::poseidon::poseidon_node_hash(left, right)
+}
+
+/// Hashes a Merkle tree's leaves and internal nodes. `HashSuite` already
+/// offers runtime-selectable hashing (useful when the suite is data the
+/// verifier reads out of the journal, as `PaymentInstructionInput` does),
+/// but every branch still goes through a `match`; a type implementing
+/// `Hasher` instead gets monomorphized into `GenericMerkleTree`/
+/// `verify_merkle_proof_generic` with no suite dispatch at all, for callers
+/// who fix the suite at compile time (e.g. a guest built once per
+/// deployment's chosen hash backend).
+pub trait Hasher {
+    /// Hash a single tagged field/leaf preimage.
+    fn hash_leaf(preimage: &[u8], tag: u8) -> [u8; 32];
+    /// Combine two child nodes into their parent.
+    fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32];
+}
+
+/// Keccak256 end-to-end, matching `HashSuite::KeccakOnly` and today's
+/// on-chain-verifiable default.
+pub struct Keccak256Hasher;
+
+impl Hasher for Keccak256Hasher {
+    fn hash_leaf(preimage: &[u8], tag: u8) -> [u8; 32] {
+        let mut tagged = preimage.to_vec();
+        tagged.push(tag);
+        keccak256(&tagged)
+    }
+
+    fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        crate::hashing::keccak256_node_hash(left, right)
+    }
+}
+
+/// The real ZK-friendly backend: a Poseidon sponge (see [`crate::poseidon`])
+/// for both leaf and node hashing, so proving cost for a Merkle path drops
+/// by orders of magnitude versus `Keccak256Hasher` inside a zk circuit.
+pub struct PoseidonHasher;
+
+impl Hasher for PoseidonHasher {
+    fn hash_leaf(preimage: &[u8], tag: u8) -> [u8; 32] {
+        crate::poseidon::poseidon_leaf_hash(preimage, tag)
+    }
+
+    fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        crate::poseidon::poseidon_node_hash(left, right)
+    }
 }
 
-/// Compute leaf hash for a field with tag (matching guest implementation)
+/// Hashes a single leaf's preimage into its commitment, independent of
+/// `Hasher`/`HashSuite`'s internal-node hashing: a leaf's field identity
+/// (debtor, creditor, amount, …) today only rides along as a loose `u8`
+/// tag passed into `MerkleTree::new`, so two fields with identical raw
+/// bytes hash identically and the tag isn't cryptographically bound into
+/// the commitment. A `LeafHasher` that folds the field's role into the
+/// hash itself (as opposed to appending a tag byte the verifier could in
+/// principle omit or a circuit could forget to check) closes that off at
+/// the leaf-hashing layer, without touching `HashSuite::node_hash` or any
+/// existing root.
+pub trait LeafHasher {
+    /// Hash a single tagged field/leaf preimage into its commitment.
+    fn hash_leaf(preimage: &[u8], tag: u8) -> [u8; 32];
+}
+
+/// Today's behavior: `compute_leaf_hash`, i.e. `sha256(preimage || tag)`.
+/// Existing proofs and roots stay verifiable under this impl.
+pub struct DefaultLeafHasher;
+
+impl LeafHasher for DefaultLeafHasher {
+    fn hash_leaf(preimage: &[u8], tag: u8) -> [u8; 32] {
+        compute_leaf_hash(preimage, tag)
+    }
+}
+
+/// Binds a field's semantic role into its commitment via a BLAKE2b
+/// personalization string, the same mechanism Zcash's equihash code uses
+/// (`Blake2bParams::new().personal(&pers)`) to separate hash domains
+/// without extra preimage bytes the tag-based schemes rely on the
+/// circuit to check. Each of the five payment fields gets its own fixed
+/// 16-byte ISO 20022-derived personalization, so e.g. an amount leaf can
+/// never be replayed as a currency leaf even if the two fields' raw bytes
+/// happen to coincide.
+pub struct PersonalizedBlake2bLeafHasher;
+
+impl PersonalizedBlake2bLeafHasher {
+    /// 16-byte BLAKE2b personalization strings, one per `tag` used by
+    /// `PaymentInstructionGenerator` (1=Dbtr, 2=Cdtr, 3=InstdAmt, 4=Ccy,
+    /// 5=ReqdExctnDt). Tag `0` has no assigned field today and falls back
+    /// to a generic personalization in `personalization_for`.
+    const DEBTOR_PERSONAL: &'static [u8; 16] = b"ISO20022:Dbtr\0\0\0";
+    const CREDITOR_PERSONAL: &'static [u8; 16] = b"ISO20022:Cdtr\0\0\0";
+    const AMOUNT_PERSONAL: &'static [u8; 16] = b"ISO20022:Amt\0\0\0\0";
+    const CURRENCY_PERSONAL: &'static [u8; 16] = b"ISO20022:Ccy\0\0\0\0";
+    const EXPIRY_PERSONAL: &'static [u8; 16] = b"ISO20022:Expiry\0";
+    const GENERIC_PERSONAL: &'static [u8; 16] = b"permERC20:leaf\0\0";
+
+    fn personalization_for(tag: u8) -> &'static [u8; 16] {
+        match tag {
+            1 => Self::DEBTOR_PERSONAL,
+            2 => Self::CREDITOR_PERSONAL,
+            3 => Self::AMOUNT_PERSONAL,
+            4 => Self::CURRENCY_PERSONAL,
+            5 => Self::EXPIRY_PERSONAL,
+            _ => Self::GENERIC_PERSONAL,
+        }
+    }
+}
+
+impl LeafHasher for PersonalizedBlake2bLeafHasher {
+    fn hash_leaf(preimage: &[u8], tag: u8) -> [u8; 32] {
+        let personal = Self::personalization_for(tag);
+        let hash = blake2b_simd::Params::new()
+            .hash_length(32)
+            .personal(personal)
+            .to_state()
+            .update(preimage)
+            .finalize();
+        let mut output = [0u8; 32];
+        output.copy_from_slice(hash.as_bytes());
+        output
+    }
+}
+
+/// Compute leaf hash for a field with tag (matching guest implementation).
+///
+/// Domain-separated from `node_hash` by shape, not just by a tag byte: a
+/// leaf hashes `preimage || tag` (arbitrary-length data plus one tag byte)
+/// while `node_hash` hashes exactly two 32-byte children, so a leaf hash can
+/// never be replayed as an internal node input. That leaves only one other
+/// avenue for two different leaf multisets to collide on the same root —
+/// duplicating an unpaired trailing leaf — which is why `merkle_tree`'s
+/// unbalanced-tree policy promotes a lone node unchanged instead.
 pub fn compute_leaf_hash(preimage: &[u8], tag: u8) -> [u8; 32] {
     let mut hasher = Sha256::new();
     hasher.update(preimage);
@@ -32,11 +244,74 @@ pub fn compute_leaf_hash(preimage: &[u8], tag: u8) -> [u8; 32] {
     output
 }
 
-/// Canonicalize JSON string according to RFC 8785 (simplified version)
+/// Canonicalize a JSON document per RFC 8785 (JSON Canonicalization
+/// Scheme): object members are recursively sorted by the UTF-16 code-unit
+/// sequence of their keys, numbers are serialized in their shortest
+/// round-tripping decimal form, strings use minimal escaping, and no
+/// insignificant whitespace is emitted. Two JSON documents that are
+/// semantically identical but differ in key order or formatting
+/// canonicalize to the same bytes, which is what lets a debtor/creditor
+/// hash commitment be recomputed identically on-chain.
+///
+/// Input that doesn't parse as JSON (e.g. a plain opaque string) is passed
+/// through unchanged rather than failing, since some callers hash
+/// non-JSON fields through this same path.
 pub fn canonicalize_json(input: &str) -> String {
-    // For simplicity, we'll assume the input is already canonicalized
-    // In a production implementation, you'd want proper JSON canonicalization
-    input.to_string()
+    match serde_json::from_str::<serde_json::Value>(input) {
+        Ok(value) => canonicalize_value(&value),
+        Err(_) => input.to_string(),
+    }
+}
+
+fn canonicalize_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "null".to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Number(n) => canonicalize_number(n),
+        serde_json::Value::String(s) => canonicalize_string(s),
+        serde_json::Value::Array(items) => {
+            let parts: Vec<String> = items.iter().map(canonicalize_value).collect();
+            format!("[{}]", parts.join(","))
+        }
+        serde_json::Value::Object(map) => {
+            // RFC 8785 §3.2.3: sort members by the UTF-16 code-unit
+            // sequence of their keys, not Rust's default UTF-8 byte order
+            // (the two only diverge outside the Basic Multilingual Plane,
+            // but the spec text is explicit about code units).
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by_key(|k| k.encode_utf16().collect::<Vec<u16>>());
+
+            let parts: Vec<String> = keys
+                .iter()
+                .map(|k| format!("{}:{}", canonicalize_string(k), canonicalize_value(&map[*k])))
+                .collect();
+            format!("{{{}}}", parts.join(","))
+        }
+    }
+}
+
+/// Serialize a number in its shortest round-tripping decimal form (RFC
+/// 8785 §3.2.2.3 defers to ECMAScript's `Number::toString`): integers that
+/// fit exactly are emitted without a decimal point, everything else uses
+/// Rust's shortest-round-trip float formatting.
+fn canonicalize_number(n: &serde_json::Number) -> String {
+    if let Some(i) = n.as_i64() {
+        return i.to_string();
+    }
+    if let Some(u) = n.as_u64() {
+        return u.to_string();
+    }
+    format!("{}", n.as_f64().unwrap_or(0.0))
+}
+
+/// Serialize a string with minimal JSON escaping (RFC 8785 §3.2.2.2):
+/// `serde_json`'s string serializer already escapes only `"`, `\`, and
+/// control characters (using the `\n`/`\r`/`\t`/`\b`/`\f` shorthands where
+/// applicable, `\u00XX` otherwise) and leaves every other character,
+/// including non-ASCII, untouched — exactly the minimal-escaping rule the
+/// spec calls for.
+fn canonicalize_string(s: &str) -> String {
+    serde_json::to_string(s).expect("string serialization cannot fail")
 }
 
 /// Convert hex string to bytes
@@ -103,10 +378,109 @@ mod tests {
         assert_eq!(decoded, bytes);
     }
 
+    #[test]
+    fn test_hash_suite_id_roundtrip() {
+        for suite in [
+            HashSuite::PoseidonKeccak,
+            HashSuite::PoseidonSha256,
+            HashSuite::KeccakOnly,
+        ] {
+            assert_eq!(HashSuite::from_id(suite.id()).unwrap(), suite);
+        }
+        assert!(HashSuite::from_id(99).is_err());
+    }
+
+    #[test]
+    fn test_hash_suite_default_is_poseidon_keccak() {
+        assert_eq!(HashSuite::default(), HashSuite::PoseidonKeccak);
+    }
+
+    #[test]
+    fn test_hash_suite_leaf_hash_is_deterministic() {
+        let hash = HashSuite::KeccakOnly.leaf_hash(b"preimage", 7u8);
+        assert_eq!(hash, HashSuite::KeccakOnly.leaf_hash(b"preimage", 7u8));
+    }
+
     #[test]
     fn test_canonicalize_json() {
         let json = r#"{"key": "value"}"#;
         let canonical = canonicalize_json(json);
-        assert_eq!(canonical, json);
+        assert_eq!(canonical, r#"{"key":"value"}"#);
+    }
+
+    #[test]
+    fn test_canonicalize_json_sorts_keys() {
+        let json = r#"{"b": 1, "a": 2}"#;
+        assert_eq!(canonicalize_json(json), r#"{"a":2,"b":1}"#);
+    }
+
+    #[test]
+    fn test_canonicalize_json_is_insensitive_to_input_order_and_whitespace() {
+        let a = r#"{ "name": "Alice", "amount": 100, "tags": ["x", "y"] }"#;
+        let b = "{\"tags\":[\"x\",\"y\"],\"amount\":100,\"name\":\"Alice\"}";
+        assert_eq!(canonicalize_json(a), canonicalize_json(b));
+    }
+
+    #[test]
+    fn test_canonicalize_json_sorts_nested_objects() {
+        let json = r#"{"outer": {"z": 1, "a": 2}}"#;
+        assert_eq!(canonicalize_json(json), r#"{"outer":{"a":2,"z":1}}"#);
+    }
+
+    #[test]
+    fn test_canonicalize_json_passes_through_non_json_input() {
+        let opaque = "not-actually-json";
+        assert_eq!(canonicalize_json(opaque), opaque);
+    }
+
+    #[test]
+    fn test_canonicalize_json_integer_has_no_decimal_point() {
+        assert_eq!(canonicalize_json("42"), "42");
+        assert_eq!(canonicalize_json("42.0"), "42");
+    }
+
+    #[test]
+    fn test_default_leaf_hasher_matches_compute_leaf_hash() {
+        let preimage = b"same bytes";
+        assert_eq!(
+            DefaultLeafHasher::hash_leaf(preimage, 3u8),
+            compute_leaf_hash(preimage, 3u8)
+        );
+    }
+
+    #[test]
+    fn test_personalized_blake2b_separates_fields_with_identical_bytes() {
+        // Same raw bytes, different field tags: an amount leaf can never
+        // collide with a currency leaf even when the underlying data
+        // happens to coincide, unlike a bare tag byte a circuit could
+        // forget to check.
+        let bytes = b"0000000000000001";
+        let amount_leaf = PersonalizedBlake2bLeafHasher::hash_leaf(bytes, 3u8);
+        let currency_leaf = PersonalizedBlake2bLeafHasher::hash_leaf(bytes, 4u8);
+        assert_ne!(amount_leaf, currency_leaf);
+    }
+
+    #[test]
+    fn test_personalized_blake2b_is_deterministic() {
+        let preimage = b"deterministic check";
+        let first = PersonalizedBlake2bLeafHasher::hash_leaf(preimage, 1u8);
+        let second = PersonalizedBlake2bLeafHasher::hash_leaf(preimage, 1u8);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_personalized_blake2b_differs_from_default_leaf_hasher() {
+        let preimage = b"domain separation check";
+        let blake = PersonalizedBlake2bLeafHasher::hash_leaf(preimage, 2u8);
+        let default = DefaultLeafHasher::hash_leaf(preimage, 2u8);
+        assert_ne!(blake, default);
+    }
+
+    #[test]
+    fn test_personalized_blake2b_unknown_tag_uses_generic_personalization() {
+        let preimage = b"unused tag";
+        let a = PersonalizedBlake2bLeafHasher::hash_leaf(preimage, 0u8);
+        let b = PersonalizedBlake2bLeafHasher::hash_leaf(preimage, 99u8);
+        assert_eq!(a, b);
     }
 }
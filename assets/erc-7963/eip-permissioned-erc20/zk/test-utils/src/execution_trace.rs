@@ -0,0 +1,234 @@
+//! Host-side, non-proving execution trace of `verify_payment_instruction`.
+//!
+//! Unlike the guest (which `panic!`s with a single formatted string on the
+//! first failed check) and [`guest_logic::verify_payment_instruction`]
+//! (which short-circuits on the first `Err`), this runs every check in
+//! order and records a [`TraceStep`] for each one regardless of whether an
+//! earlier step failed — mirroring the externality-wrapper approach of
+//! recording each sub-operation for later inspection rather than surfacing
+//! only the first failure. This lets a caller see exactly which hash,
+//! amount-bound or expiry check, or which of the five leaves' Merkle proofs,
+//! caused a rejection.
+
+use crate::crypto_utils::canonicalize_json;
+use crate::merkle_tree::{MerkleProof, MerkleTree};
+use crate::payment_instruction_generator::PaymentInstructionInput;
+use serde::Serialize;
+
+/// One check performed while verifying a payment instruction.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceStep {
+    pub check: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl TraceStep {
+    fn new(check: &str, passed: bool, detail: String) -> Self {
+        Self {
+            check: check.to_string(),
+            passed,
+            detail,
+        }
+    }
+}
+
+/// Ordered record of every check `verify_payment_instruction` performs,
+/// plus the overall pass/fail outcome (the logical AND of every step).
+#[derive(Debug, Clone, Serialize)]
+pub struct VerificationTrace {
+    pub steps: Vec<TraceStep>,
+    pub passed: bool,
+}
+
+/// Run every check `guest_logic::verify_payment_instruction` performs
+/// against `input`, recording a [`TraceStep`] per check instead of
+/// returning on the first failure.
+pub fn trace_verify_payment_instruction(input: &PaymentInstructionInput) -> VerificationTrace {
+    let suite = input.hash_suite;
+    let mut steps = Vec::new();
+
+    let canonical_debtor = canonicalize_json(&input.debtor_data);
+    let computed_debtor_hash = suite.field_hash(canonical_debtor.as_bytes());
+    let debtor_hash_ok = computed_debtor_hash == input.debtor_hash;
+    steps.push(TraceStep::new(
+        "debtor_hash",
+        debtor_hash_ok,
+        format!(
+            "computed {} vs expected {}",
+            hex_preview(&computed_debtor_hash),
+            hex_preview(&input.debtor_hash)
+        ),
+    ));
+
+    let canonical_creditor = canonicalize_json(&input.creditor_data);
+    let computed_creditor_hash = suite.field_hash(canonical_creditor.as_bytes());
+    let creditor_hash_ok = computed_creditor_hash == input.creditor_hash;
+    steps.push(TraceStep::new(
+        "creditor_hash",
+        creditor_hash_ok,
+        format!(
+            "computed {} vs expected {}",
+            hex_preview(&computed_creditor_hash),
+            hex_preview(&input.creditor_hash)
+        ),
+    ));
+
+    let computed_currency_hash = suite.field_hash(input.currency.as_bytes());
+    let currency_hash_ok = computed_currency_hash == input.currency_hash;
+    steps.push(TraceStep::new(
+        "currency_hash",
+        currency_hash_ok,
+        format!(
+            "computed {} vs expected {}",
+            hex_preview(&computed_currency_hash),
+            hex_preview(&input.currency_hash)
+        ),
+    ));
+
+    let amount_ok = input.amount_value >= input.min_amount_milli
+        && input.amount_value <= input.max_amount_milli;
+    steps.push(TraceStep::new(
+        "amount_bounds",
+        amount_ok,
+        format!(
+            "{} within [{}, {}]: {}",
+            input.amount_value, input.min_amount_milli, input.max_amount_milli, amount_ok
+        ),
+    ));
+
+    let expiry_parse = input.execution_date.replace('-', "").parse::<u64>();
+    let (expiry_ok, expiry_detail) = match expiry_parse {
+        Ok(parsed) if parsed == input.expiry => {
+            (true, format!("parsed {} matches expiry {}", parsed, input.expiry))
+        }
+        Ok(parsed) => (
+            false,
+            format!("parsed {} does not match expiry {}", parsed, input.expiry),
+        ),
+        Err(e) => (false, format!("failed to parse execution_date: {}", e)),
+    };
+    steps.push(TraceStep::new("expiry", expiry_ok, expiry_detail));
+
+    let amount_bytes = input.amount_value.to_be_bytes();
+    let expiry_bytes = input.expiry.to_be_bytes();
+
+    for (check, preimage, tag, siblings, directions) in [
+        (
+            "debtor_merkle_proof",
+            input.debtor_hash.as_slice(),
+            1u8,
+            input.debtor_proof_siblings.as_slice(),
+            input.debtor_proof_directions.as_slice(),
+        ),
+        (
+            "creditor_merkle_proof",
+            input.creditor_hash.as_slice(),
+            2u8,
+            input.creditor_proof_siblings.as_slice(),
+            input.creditor_proof_directions.as_slice(),
+        ),
+        (
+            "amount_merkle_proof",
+            amount_bytes.as_slice(),
+            3u8,
+            input.amount_proof_siblings.as_slice(),
+            input.amount_proof_directions.as_slice(),
+        ),
+        (
+            "currency_merkle_proof",
+            input.currency_hash.as_slice(),
+            4u8,
+            input.currency_proof_siblings.as_slice(),
+            input.currency_proof_directions.as_slice(),
+        ),
+        (
+            "expiry_merkle_proof",
+            expiry_bytes.as_slice(),
+            5u8,
+            input.expiry_proof_siblings.as_slice(),
+            input.expiry_proof_directions.as_slice(),
+        ),
+    ] {
+        let leaf = suite.leaf_hash(preimage, tag);
+        let proof = MerkleProof {
+            siblings: siblings.to_vec(),
+            directions: directions.to_vec(),
+        };
+        let proof_ok = MerkleTree::verify_proof_with_suite(&leaf, &proof, &input.root, suite);
+        steps.push(TraceStep::new(
+            check,
+            proof_ok,
+            format!(
+                "leaf {} against root {} over {} path step(s): {}",
+                hex_preview(&leaf),
+                hex_preview(&input.root),
+                directions.len(),
+                if proof_ok { "reconstructed root" } else { "root mismatch" }
+            ),
+        ));
+    }
+
+    let passed = steps.iter().all(|step| step.passed);
+    VerificationTrace { steps, passed }
+}
+
+fn hex_preview(bytes: &[u8]) -> String {
+    format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_data::MockData;
+
+    #[test]
+    fn test_trace_passes_for_valid_input() {
+        let mut generator = crate::payment_instruction_generator::PaymentInstructionGenerator::new();
+        let input = generator.generate_valid_input();
+
+        let trace = trace_verify_payment_instruction(&input);
+        assert!(trace.passed);
+        assert_eq!(trace.steps.len(), 10);
+        assert!(trace.steps.iter().all(|step| step.passed));
+    }
+
+    #[test]
+    fn test_trace_identifies_failing_leaf() {
+        let mut input = MockData::simple_valid_input();
+        input.debtor_proof_siblings = vec![[0xffu8; 32]];
+        input.debtor_proof_directions = vec![0];
+
+        let trace = trace_verify_payment_instruction(&input);
+        assert!(!trace.passed);
+
+        let debtor_step = trace
+            .steps
+            .iter()
+            .find(|step| step.check == "debtor_merkle_proof")
+            .unwrap();
+        assert!(!debtor_step.passed);
+
+        // Every other step is unaffected by the tampered proof.
+        let creditor_step = trace
+            .steps
+            .iter()
+            .find(|step| step.check == "creditor_merkle_proof")
+            .unwrap();
+        assert!(creditor_step.passed);
+    }
+
+    #[test]
+    fn test_trace_reports_amount_out_of_bounds() {
+        let mut input = MockData::simple_valid_input();
+        input.amount_value = input.min_amount_milli - 1;
+
+        let trace = trace_verify_payment_instruction(&input);
+        let amount_step = trace
+            .steps
+            .iter()
+            .find(|step| step.check == "amount_bounds")
+            .unwrap();
+        assert!(!amount_step.passed);
+    }
+}
@@ -0,0 +1,211 @@
+//! Conformance-fixture format for `PaymentInstructionInput`/`PaymentInstructionOutput`
+//! pairs, modeled on the Ethereum JSON test-vector style (a typed
+//! `{"input": ..., "expect": ...}` document) so independent verifier
+//! implementations can replay the exact corpus `PaymentInstructionGenerator`
+//! otherwise only produces procedurally, including negative vectors like
+//! `generate_invalid_merkle_proof`.
+
+use crate::payment_instruction_generator::{PaymentInstructionInput, PaymentInstructionOutput};
+use crate::test_helpers::generate_and_verify_proof;
+use crate::TestConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One JSON-serializable conformance fixture: a full `PaymentInstructionInput`
+/// paired with the behavior a conforming implementation is expected to
+/// exhibit when it feeds that input through proof generation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentInstructionFixture {
+    pub input: PaymentInstructionInput,
+    pub expect: FixtureExpectation,
+}
+
+/// What `run_fixture_suite` checks a fixture's input against: either proof
+/// generation succeeds and commits exactly `output`, or it's a negative
+/// vector expected to fail outright (e.g. a corrupted Merkle proof).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum FixtureExpectation {
+    Valid { output: PaymentInstructionOutput },
+    Invalid,
+}
+
+impl PaymentInstructionFixture {
+    pub fn valid(input: PaymentInstructionInput, output: PaymentInstructionOutput) -> Self {
+        Self {
+            input,
+            expect: FixtureExpectation::Valid { output },
+        }
+    }
+
+    pub fn invalid(input: PaymentInstructionInput) -> Self {
+        Self {
+            input,
+            expect: FixtureExpectation::Invalid,
+        }
+    }
+
+    /// Write this fixture to `path` as pretty-printed JSON.
+    pub fn dump_fixture(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .expect("PaymentInstructionFixture always serializes to JSON");
+        fs::write(path, json)
+    }
+
+    /// Load a fixture previously written by `dump_fixture`.
+    pub fn load_fixture(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read fixture {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("failed to parse fixture {}: {}", path.display(), e))
+    }
+}
+
+/// One fixture's pass/fail outcome from `run_fixture_suite`, naming which
+/// file it came from so a failing conformance run points straight at the
+/// offending vector instead of only reporting an aggregate count.
+#[derive(Debug)]
+pub struct FixtureResult {
+    pub path: PathBuf,
+    pub passed: bool,
+    pub detail: String,
+}
+
+/// Load every `.json` fixture in `dir`, feed each input through
+/// `generate_and_verify_proof`, and check the observed outcome against the
+/// fixture's `expect`. Returns one `FixtureResult` per fixture rather than
+/// stopping at the first mismatch, so a whole corpus run reports every
+/// discrepancy at once.
+pub fn run_fixture_suite(dir: &Path, config: &TestConfig) -> Result<Vec<FixtureResult>, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("failed to read fixture directory {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+        .collect();
+    paths.sort();
+
+    let mut results = Vec::with_capacity(paths.len());
+    for path in paths {
+        let fixture = match PaymentInstructionFixture::load_fixture(&path) {
+            Ok(fixture) => fixture,
+            Err(detail) => {
+                results.push(FixtureResult {
+                    path,
+                    passed: false,
+                    detail,
+                });
+                continue;
+            }
+        };
+
+        let outcome = generate_and_verify_proof(&fixture.input, config);
+        let (passed, detail) = match (&fixture.expect, outcome) {
+            (FixtureExpectation::Valid { output: expected }, Ok((actual, _))) if &actual == expected => {
+                (true, "journal matched expected output".to_string())
+            }
+            (FixtureExpectation::Valid { output: expected }, Ok((actual, _))) => (
+                false,
+                format!(
+                    "journal mismatch: expected {:?}, got {:?}",
+                    expected, actual
+                ),
+            ),
+            (FixtureExpectation::Valid { .. }, Err(error)) => (
+                false,
+                format!("expected a valid proof but generation failed: {}", error),
+            ),
+            (FixtureExpectation::Invalid, Err(_)) => (true, "failed as expected".to_string()),
+            (FixtureExpectation::Invalid, Ok(_)) => (
+                false,
+                "expected proof generation to fail, but it succeeded".to_string(),
+            ),
+        };
+
+        results.push(FixtureResult {
+            path,
+            passed,
+            detail,
+        });
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto_utils::HashSuite;
+    use crate::payment_instruction_generator::PaymentInstructionGenerator;
+    use tempfile::tempdir;
+
+    fn sample_output() -> PaymentInstructionOutput {
+        PaymentInstructionOutput {
+            root: [1u8; 32],
+            debtor_hash: [2u8; 32],
+            creditor_hash: [3u8; 32],
+            min_amount_milli: 1_000,
+            max_amount_milli: 5_000,
+            currency_hash: [4u8; 32],
+            expiry: 20_300_101,
+            hash_suite: HashSuite::default(),
+        }
+    }
+
+    #[test]
+    fn test_dump_and_load_fixture_round_trips_valid_vector() {
+        let mut generator = PaymentInstructionGenerator::new();
+        let input = generator.generate_valid_input();
+        let fixture = PaymentInstructionFixture::valid(input.clone(), sample_output());
+
+        let dir = tempdir().expect("tempdir creation should succeed");
+        let path = dir.path().join("valid.json");
+        fixture.dump_fixture(&path).expect("dump_fixture should succeed");
+
+        let loaded = PaymentInstructionFixture::load_fixture(&path).expect("load_fixture should succeed");
+        assert_eq!(loaded.expect, fixture.expect);
+        assert_eq!(loaded.input.root, input.root);
+    }
+
+    #[test]
+    fn test_dump_and_load_fixture_round_trips_invalid_vector() {
+        let mut generator = PaymentInstructionGenerator::new();
+        let input = generator.generate_invalid_merkle_proof();
+        let fixture = PaymentInstructionFixture::invalid(input);
+
+        let dir = tempdir().expect("tempdir creation should succeed");
+        let path = dir.path().join("invalid.json");
+        fixture.dump_fixture(&path).expect("dump_fixture should succeed");
+
+        let loaded = PaymentInstructionFixture::load_fixture(&path).expect("load_fixture should succeed");
+        assert_eq!(loaded.expect, FixtureExpectation::Invalid);
+    }
+
+    #[test]
+    fn test_run_fixture_suite_skips_non_json_files_and_sorts_by_path() {
+        let dir = tempdir().expect("tempdir creation should succeed");
+        fs::write(dir.path().join("README.md"), b"not a fixture").unwrap();
+
+        let mut generator = PaymentInstructionGenerator::new();
+        for name in ["b-invalid.json", "a-invalid.json"] {
+            let input = generator.generate_invalid_merkle_proof();
+            PaymentInstructionFixture::invalid(input)
+                .dump_fixture(&dir.path().join(name))
+                .unwrap();
+        }
+
+        let config = crate::test_helpers::create_test_config(crate::test_helpers::TestScenario::Fast);
+        let results = run_fixture_suite(dir.path(), &config).expect("run_fixture_suite should succeed");
+        assert_eq!(results.len(), 2);
+        assert!(results[0].path.ends_with("a-invalid.json"));
+        assert!(results[1].path.ends_with("b-invalid.json"));
+    }
+
+    #[test]
+    fn test_run_fixture_suite_errors_on_missing_directory() {
+        let config = crate::test_helpers::create_test_config(crate::test_helpers::TestScenario::Fast);
+        let result = run_fixture_suite(Path::new("/nonexistent/fixture/dir"), &config);
+        assert!(result.is_err());
+    }
+}
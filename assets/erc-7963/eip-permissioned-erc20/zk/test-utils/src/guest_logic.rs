@@ -1,25 +1,30 @@
-use crate::crypto_utils::{canonicalize_json, compute_leaf_hash, keccak256};
-use crate::merkle_tree::{MerkleProof, MerkleTree};
+use crate::crypto_utils::{canonicalize_json, keccak256, HashSuite};
+use crate::merkle_tree::{MerkleProof, MerkleTree, MultiProof};
+use std::collections::HashMap;
 use crate::payment_instruction_generator::{PaymentInstructionInput, PaymentInstructionOutput};
 
-/// Main verification logic (duplicated from guest for testing)
+/// Main verification logic (duplicated from guest for testing). Hashes are
+/// recomputed under `input.hash_suite` rather than a fixed pairing, so the
+/// suite committed by the generator is the suite the verifier checks against.
 pub fn verify_payment_instruction(input: &PaymentInstructionInput) -> Result<PaymentInstructionOutput, String> {
+    let suite = input.hash_suite;
+
     // 1. Verify debtor hash
     let canonical_debtor = canonicalize_json(&input.debtor_data);
-    let computed_debtor_hash = keccak256(canonical_debtor.as_bytes());
+    let computed_debtor_hash = suite.field_hash(canonical_debtor.as_bytes());
     if computed_debtor_hash != input.debtor_hash {
         return Err("Debtor hash mismatch".to_string());
     }
 
     // 2. Verify creditor hash
     let canonical_creditor = canonicalize_json(&input.creditor_data);
-    let computed_creditor_hash = keccak256(canonical_creditor.as_bytes());
+    let computed_creditor_hash = suite.field_hash(canonical_creditor.as_bytes());
     if computed_creditor_hash != input.creditor_hash {
         return Err("Creditor hash mismatch".to_string());
     }
 
     // 3. Verify currency hash
-    let computed_currency_hash = keccak256(input.currency.as_bytes());
+    let computed_currency_hash = suite.field_hash(input.currency.as_bytes());
     if computed_currency_hash != input.currency_hash {
         return Err("Currency hash mismatch".to_string());
     }
@@ -43,50 +48,50 @@ pub fn verify_payment_instruction(input: &PaymentInstructionInput) -> Result<Pay
     }
 
     // 6. Verify Merkle proofs
-    let debtor_leaf = compute_leaf_hash(&input.debtor_hash, 1u8);
+    let debtor_leaf = suite.leaf_hash(&input.debtor_hash, 1u8);
     let debtor_proof = MerkleProof {
         siblings: input.debtor_proof_siblings.clone(),
         directions: input.debtor_proof_directions.clone(),
     };
-    if !MerkleTree::verify_proof(&debtor_leaf, &debtor_proof, &input.root) {
+    if !MerkleTree::verify_proof_with_suite(&debtor_leaf, &debtor_proof, &input.root, suite) {
         return Err("Invalid debtor Merkle proof".to_string());
     }
 
-    let creditor_leaf = compute_leaf_hash(&input.creditor_hash, 2u8);
+    let creditor_leaf = suite.leaf_hash(&input.creditor_hash, 2u8);
     let creditor_proof = MerkleProof {
         siblings: input.creditor_proof_siblings.clone(),
         directions: input.creditor_proof_directions.clone(),
     };
-    if !MerkleTree::verify_proof(&creditor_leaf, &creditor_proof, &input.root) {
+    if !MerkleTree::verify_proof_with_suite(&creditor_leaf, &creditor_proof, &input.root, suite) {
         return Err("Invalid creditor Merkle proof".to_string());
     }
 
     let amount_bytes = input.amount_value.to_be_bytes();
-    let amount_leaf = compute_leaf_hash(&amount_bytes, 3u8);
+    let amount_leaf = suite.leaf_hash(&amount_bytes, 3u8);
     let amount_proof = MerkleProof {
         siblings: input.amount_proof_siblings.clone(),
         directions: input.amount_proof_directions.clone(),
     };
-    if !MerkleTree::verify_proof(&amount_leaf, &amount_proof, &input.root) {
+    if !MerkleTree::verify_proof_with_suite(&amount_leaf, &amount_proof, &input.root, suite) {
         return Err("Invalid amount Merkle proof".to_string());
     }
 
-    let currency_leaf = compute_leaf_hash(&input.currency_hash, 4u8);
+    let currency_leaf = suite.leaf_hash(&input.currency_hash, 4u8);
     let currency_proof = MerkleProof {
         siblings: input.currency_proof_siblings.clone(),
         directions: input.currency_proof_directions.clone(),
     };
-    if !MerkleTree::verify_proof(&currency_leaf, &currency_proof, &input.root) {
+    if !MerkleTree::verify_proof_with_suite(&currency_leaf, &currency_proof, &input.root, suite) {
         return Err("Invalid currency Merkle proof".to_string());
     }
 
     let expiry_bytes = input.expiry.to_be_bytes();
-    let expiry_leaf = compute_leaf_hash(&expiry_bytes, 5u8);
+    let expiry_leaf = suite.leaf_hash(&expiry_bytes, 5u8);
     let expiry_proof = MerkleProof {
         siblings: input.expiry_proof_siblings.clone(),
         directions: input.expiry_proof_directions.clone(),
     };
-    if !MerkleTree::verify_proof(&expiry_leaf, &expiry_proof, &input.root) {
+    if !MerkleTree::verify_proof_with_suite(&expiry_leaf, &expiry_proof, &input.root, suite) {
         return Err("Invalid expiry Merkle proof".to_string());
     }
 
@@ -99,6 +104,7 @@ pub fn verify_payment_instruction(input: &PaymentInstructionInput) -> Result<Pay
         max_amount_milli: input.max_amount_milli,
         currency_hash: input.currency_hash,
         expiry: input.expiry,
+        hash_suite: suite,
     })
 }
 
@@ -117,6 +123,332 @@ pub fn verify_merkle_proof(
     MerkleTree::verify_proof(leaf, &proof, root)
 }
 
+/// Verify a batch of leaves against one root via a [`MultiProof`], the
+/// guest-side counterpart to `MerkleTree::generate_multiproof`. Recomputes
+/// the tree level by level: at each level, a node's hash either comes from
+/// `leaves`/a lower level's freshly-computed parent, or — when its sibling
+/// isn't already known — from the next unconsumed entry in
+/// `multiproof.siblings`. Fails if `leaves` doesn't match the indices the
+/// proof was built for, if the siblings run out before the root is reached,
+/// if any sibling is left unconsumed (a proof can't be short or padded), or
+/// if the recomputed root doesn't match `root`.
+pub fn verify_multi_merkle_proof(
+    root: &[u8; 32],
+    leaves: &[(usize, [u8; 32])],
+    multiproof: &MultiProof,
+    suite: HashSuite,
+) -> bool {
+    if leaves.iter().any(|&(idx, _)| idx >= multiproof.leaf_count) {
+        return false;
+    }
+
+    let mut given_indices: Vec<usize> = leaves.iter().map(|&(idx, _)| idx).collect();
+    given_indices.sort_unstable();
+    let mut expected_indices = multiproof.leaf_indices.clone();
+    expected_indices.sort_unstable();
+    if given_indices != expected_indices {
+        return false;
+    }
+
+    let mut current: HashMap<usize, [u8; 32]> = leaves.iter().copied().collect();
+    let mut sibling_cursor = 0usize;
+    let mut level_size = multiproof.leaf_count;
+
+    while level_size > 1 {
+        let mut sorted_known: Vec<usize> = current.keys().copied().collect();
+        sorted_known.sort_unstable();
+
+        let mut processed = std::collections::HashSet::new();
+        let mut next_level = HashMap::new();
+
+        for idx in sorted_known {
+            if processed.contains(&idx) {
+                continue;
+            }
+
+            let current_hash = match current.get(&idx) {
+                Some(&hash) => hash,
+                None => return false,
+            };
+
+            if idx % 2 == 0 && idx + 1 >= level_size {
+                // Lone trailing node: promoted unchanged, matching the
+                // unbalanced-tree policy in `merkle_tree`.
+                processed.insert(idx);
+                next_level.insert(idx / 2, current_hash);
+                continue;
+            }
+
+            let sibling_idx = if idx % 2 == 0 { idx + 1 } else { idx - 1 };
+            let sibling_hash = match current.get(&sibling_idx) {
+                Some(&hash) => hash,
+                None => match multiproof.siblings.get(sibling_cursor) {
+                    Some(&hash) => {
+                        sibling_cursor += 1;
+                        hash
+                    }
+                    None => return false,
+                },
+            };
+
+            let parent_hash = if idx % 2 == 0 {
+                suite.node_hash(&current_hash, &sibling_hash)
+            } else {
+                suite.node_hash(&sibling_hash, &current_hash)
+            };
+
+            processed.insert(idx);
+            processed.insert(sibling_idx);
+            next_level.insert(idx / 2, parent_hash);
+        }
+
+        current = next_level;
+        level_size = level_size.div_ceil(2);
+    }
+
+    sibling_cursor == multiproof.siblings.len()
+        && current.get(&0).is_some_and(|hash| hash == root)
+}
+
+/// One decoded RLP item: either a byte string or a list of further items.
+/// `Vec<u8>` values returned from [`rlp_decode`] are owned copies sliced out
+/// of the original node bytes, rather than borrowing, so a decoded item can
+/// outlive the `Vec<u8>` it was parsed from (needed once an embedded child
+/// node is decoded out of its parent's already-decoded bytes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RlpItem {
+    Bytes(Vec<u8>),
+    List(Vec<RlpItem>),
+}
+
+/// Decode exactly one RLP-encoded item, erroring if any bytes are left over.
+fn rlp_decode(data: &[u8]) -> Result<RlpItem, String> {
+    let (item, consumed) = rlp_decode_one(data)?;
+    if consumed != data.len() {
+        return Err("trailing bytes after a single RLP item".to_string());
+    }
+    Ok(item)
+}
+
+fn rlp_decode_one(data: &[u8]) -> Result<(RlpItem, usize), String> {
+    let prefix = *data.first().ok_or("cannot decode RLP from empty input")?;
+    match prefix {
+        0x00..=0x7f => Ok((RlpItem::Bytes(vec![prefix]), 1)),
+        0x80..=0xb7 => {
+            let len = (prefix - 0x80) as usize;
+            let payload = data.get(1..1 + len).ok_or("RLP short string runs past end of input")?;
+            Ok((RlpItem::Bytes(payload.to_vec()), 1 + len))
+        }
+        0xb8..=0xbf => {
+            let len_of_len = (prefix - 0xb7) as usize;
+            let len = rlp_be_len(data.get(1..1 + len_of_len).ok_or("RLP long string length prefix runs past end of input")?)?;
+            let start = 1 + len_of_len;
+            let payload = data.get(start..start + len).ok_or("RLP long string runs past end of input")?;
+            Ok((RlpItem::Bytes(payload.to_vec()), start + len))
+        }
+        0xc0..=0xf7 => {
+            let len = (prefix - 0xc0) as usize;
+            let end = 1 + len;
+            if data.len() < end {
+                return Err("RLP short list runs past end of input".to_string());
+            }
+            Ok((RlpItem::List(rlp_decode_list_payload(&data[1..end])?), end))
+        }
+        0xf8..=0xff => {
+            let len_of_len = (prefix - 0xf7) as usize;
+            let len = rlp_be_len(data.get(1..1 + len_of_len).ok_or("RLP long list length prefix runs past end of input")?)?;
+            let start = 1 + len_of_len;
+            let end = start + len;
+            if data.len() < end {
+                return Err("RLP long list runs past end of input".to_string());
+            }
+            Ok((RlpItem::List(rlp_decode_list_payload(&data[start..end])?), end))
+        }
+    }
+}
+
+fn rlp_decode_list_payload(payload: &[u8]) -> Result<Vec<RlpItem>, String> {
+    let mut items = Vec::new();
+    let mut offset = 0;
+    while offset < payload.len() {
+        let (item, consumed) = rlp_decode_one(&payload[offset..])?;
+        items.push(item);
+        offset += consumed;
+    }
+    Ok(items)
+}
+
+fn rlp_be_len(bytes: &[u8]) -> Result<usize, String> {
+    if bytes.len() > std::mem::size_of::<usize>() {
+        return Err("RLP length prefix too large".to_string());
+    }
+    let mut buf = [0u8; 8];
+    buf[8 - bytes.len()..].copy_from_slice(bytes);
+    Ok(u64::from_be_bytes(buf) as usize)
+}
+
+fn bytes_to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    let mut nibbles = Vec::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        nibbles.push(b >> 4);
+        nibbles.push(b & 0x0f);
+    }
+    nibbles
+}
+
+/// Decode a hex-prefix (compact) encoded path, as used by MPT extension and
+/// leaf nodes. The first nibble's value selects the node kind and parity:
+/// 0/1 = extension (even/odd path length), 2/3 = leaf (even/odd). An odd
+/// flag nibble carries the path's first nibble in its low bits; an even
+/// flag nibble is followed by a zero-padding nibble before the real path.
+fn decode_compact_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), String> {
+    let flag_nibble = encoded.first().ok_or("compact-encoded path is empty")? >> 4;
+    let is_leaf = matches!(flag_nibble, 2 | 3);
+    let is_odd = matches!(flag_nibble, 1 | 3);
+    if !matches!(flag_nibble, 0..=3) {
+        return Err(format!("invalid compact-path flag nibble: {}", flag_nibble));
+    }
+
+    let mut nibbles = bytes_to_nibbles(encoded);
+    if is_odd {
+        nibbles.remove(0);
+    } else {
+        nibbles.drain(0..2);
+    }
+    Ok((nibbles, is_leaf))
+}
+
+/// Where the next trie node to process comes from: a separate entry in the
+/// proof's flat `nodes` list, referenced by its keccak256 hash, or a node
+/// embedded directly inside its parent's RLP encoding because it's shorter
+/// than 32 bytes and so was never given its own hash.
+enum NodeSource {
+    Hash([u8; 32]),
+    Embedded(RlpItem),
+}
+
+fn child_to_node_source(item: &RlpItem) -> Result<NodeSource, String> {
+    match item {
+        RlpItem::Bytes(b) if b.is_empty() => {
+            Err("empty branch/extension slot: key is not present in this trie".to_string())
+        }
+        RlpItem::Bytes(b) if b.len() == 32 => {
+            let mut hash = [0u8; 32];
+            hash.copy_from_slice(b);
+            Ok(NodeSource::Hash(hash))
+        }
+        RlpItem::Bytes(b) => rlp_decode(b).map(NodeSource::Embedded),
+        RlpItem::List(_) => {
+            Err("branch/extension slot must be an RLP byte string (a hash or an embedded node)".to_string())
+        }
+    }
+}
+
+/// Verify that `key` maps to `expected_value` in the Ethereum state/storage
+/// trie rooted at `state_root`, given the path of trie nodes from the root
+/// down to the leaf. `nodes` holds one RLP-encoded node per proof step that
+/// was referenced by hash; nodes embedded inline (shorter than 32 bytes,
+/// see [`NodeSource::Embedded`]) don't get their own entry.
+///
+/// For an account proof, `key` is `keccak256(address)` and `expected_value`
+/// is the RLP encoding of `[nonce, balance, storageRoot, codeHash]`; for a
+/// storage proof, `key` is `keccak256(slot)` and `expected_value` is the
+/// RLP encoding of the stored word. This only anchors the single
+/// debtor/creditor commitment or settlement nonce the caller names — it
+/// doesn't re-derive `state_root` itself, which a caller must obtain from a
+/// trusted source (e.g. a block header already verified some other way).
+pub fn verify_mpt_proof(
+    nodes: &[Vec<u8>],
+    state_root: &[u8; 32],
+    key: &[u8],
+    expected_value: &[u8],
+) -> Result<bool, String> {
+    let first = nodes.first().ok_or("MPT proof has no nodes")?;
+    if keccak256(first) != *state_root {
+        return Err("first proof node does not hash to the given state root".to_string());
+    }
+
+    let nibbles = bytes_to_nibbles(key);
+    let mut nibble_offset = 0usize;
+    let mut next_node_index = 0usize;
+    let mut current = NodeSource::Hash(*state_root);
+
+    loop {
+        let decoded = match current {
+            NodeSource::Hash(expected_hash) => {
+                let node_bytes = nodes
+                    .get(next_node_index)
+                    .ok_or("proof ran out of nodes before reaching a terminal node")?;
+                if keccak256(node_bytes) != expected_hash {
+                    return Err(format!(
+                        "node {} does not hash to its expected reference",
+                        next_node_index
+                    ));
+                }
+                next_node_index += 1;
+                rlp_decode(node_bytes)?
+            }
+            NodeSource::Embedded(item) => item,
+        };
+
+        let items = match decoded {
+            RlpItem::List(items) => items,
+            RlpItem::Bytes(_) => return Err("trie node is not an RLP list".to_string()),
+        };
+
+        match items.len() {
+            17 => {
+                if nibble_offset == nibbles.len() {
+                    let value = match &items[16] {
+                        RlpItem::Bytes(b) => b,
+                        RlpItem::List(_) => return Err("branch value slot is not bytes".to_string()),
+                    };
+                    if value.is_empty() {
+                        return Err("branch value slot is empty: key is not present in this trie".to_string());
+                    }
+                    return Ok(value.as_slice() == expected_value);
+                }
+
+                let nibble = nibbles[nibble_offset] as usize;
+                nibble_offset += 1;
+                current = child_to_node_source(&items[nibble])?;
+            }
+            2 => {
+                let path_bytes = match &items[0] {
+                    RlpItem::Bytes(b) => b,
+                    RlpItem::List(_) => return Err("node path is not an RLP byte string".to_string()),
+                };
+                let (path_nibbles, is_leaf) = decode_compact_path(path_bytes)?;
+
+                let remaining = &nibbles[nibble_offset..];
+                if remaining.len() < path_nibbles.len() || remaining[..path_nibbles.len()] != path_nibbles[..] {
+                    return Err("shared-nibble path does not match the remaining key".to_string());
+                }
+                nibble_offset += path_nibbles.len();
+
+                if is_leaf {
+                    if nibble_offset != nibbles.len() {
+                        return Err("leaf node reached before consuming the full key".to_string());
+                    }
+                    let value = match &items[1] {
+                        RlpItem::Bytes(b) => b,
+                        RlpItem::List(_) => return Err("leaf value is not an RLP byte string".to_string()),
+                    };
+                    return Ok(value.as_slice() == expected_value);
+                }
+
+                current = child_to_node_source(&items[1])?;
+            }
+            other => return Err(format!("trie node has unexpected arity {} (expected 2 or 17)", other)),
+        }
+    }
+}
+
+/// The state-trie key for an account: `keccak256(address)`.
+pub fn account_trie_key(address: &[u8; 20]) -> [u8; 32] {
+    keccak256(address)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,4 +483,135 @@ mod tests {
             1u8
         ));
     }
+
+    // --- verify_mpt_proof fixtures -----------------------------------
+    //
+    // Minimal RLP encoders, kept test-only, for building small hand-rolled
+    // tries rather than depending on fixture data pulled from a live chain.
+
+    fn rlp_encode_bytes(data: &[u8]) -> Vec<u8> {
+        if data.len() == 1 && data[0] < 0x80 {
+            vec![data[0]]
+        } else if data.len() <= 55 {
+            let mut out = vec![0x80 + data.len() as u8];
+            out.extend_from_slice(data);
+            out
+        } else {
+            let len_bytes = rlp_test_be_len_bytes(data.len());
+            let mut out = vec![0xb7 + len_bytes.len() as u8];
+            out.extend_from_slice(&len_bytes);
+            out.extend_from_slice(data);
+            out
+        }
+    }
+
+    fn rlp_encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+        let payload: Vec<u8> = items.concat();
+        if payload.len() <= 55 {
+            let mut out = vec![0xc0 + payload.len() as u8];
+            out.extend_from_slice(&payload);
+            out
+        } else {
+            let len_bytes = rlp_test_be_len_bytes(payload.len());
+            let mut out = vec![0xf7 + len_bytes.len() as u8];
+            out.extend_from_slice(&len_bytes);
+            out.extend_from_slice(&payload);
+            out
+        }
+    }
+
+    fn rlp_test_be_len_bytes(n: usize) -> Vec<u8> {
+        let bytes = (n as u64).to_be_bytes();
+        let first_nonzero = bytes.iter().position(|&b| b != 0).unwrap_or(7);
+        bytes[first_nonzero..].to_vec()
+    }
+
+    /// Hex-prefix (compact) encode a leaf path with an even number of
+    /// nibbles, for a standalone single-leaf trie fixture.
+    fn compact_leaf_path_even(nibbles: &[u8]) -> Vec<u8> {
+        assert_eq!(nibbles.len() % 2, 0, "fixture only covers even-length paths");
+        let mut bytes = vec![0x20]; // flag nibble 2 (leaf, even) + zero padding nibble
+        for pair in nibbles.chunks(2) {
+            bytes.push((pair[0] << 4) | pair[1]);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_verify_mpt_proof_single_leaf_node() {
+        // A trie with exactly one account: the root node IS the leaf node,
+        // short enough to need no embedding. key = keccak256(b"account"),
+        // consumed entirely by the leaf's own compact-encoded path.
+        let key = keccak256(b"account");
+        let nibbles = bytes_to_nibbles(&key);
+        let value = rlp_encode_bytes(b"account-value");
+
+        let leaf_node = rlp_encode_list(&[
+            rlp_encode_bytes(&compact_leaf_path_even(&nibbles)),
+            value.clone(),
+        ]);
+        let state_root = keccak256(&leaf_node);
+
+        let result = verify_mpt_proof(&[leaf_node], &state_root, &key, &value);
+        assert_eq!(result, Ok(true));
+    }
+
+    #[test]
+    fn test_verify_mpt_proof_rejects_wrong_value() {
+        let key = keccak256(b"account");
+        let nibbles = bytes_to_nibbles(&key);
+        let value = rlp_encode_bytes(b"account-value");
+        let wrong_value = rlp_encode_bytes(b"tampered-value");
+
+        let leaf_node = rlp_encode_list(&[
+            rlp_encode_bytes(&compact_leaf_path_even(&nibbles)),
+            value,
+        ]);
+        let state_root = keccak256(&leaf_node);
+
+        let result = verify_mpt_proof(&[leaf_node], &state_root, &key, &wrong_value);
+        assert_eq!(result, Ok(false));
+    }
+
+    #[test]
+    fn test_verify_mpt_proof_rejects_root_mismatch() {
+        let key = keccak256(b"account");
+        let nibbles = bytes_to_nibbles(&key);
+        let value = rlp_encode_bytes(b"account-value");
+
+        let leaf_node = rlp_encode_list(&[
+            rlp_encode_bytes(&compact_leaf_path_even(&nibbles)),
+            value.clone(),
+        ]);
+        let wrong_root = [0xAA; 32];
+
+        let result = verify_mpt_proof(&[leaf_node], &wrong_root, &key, &value);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_decode_compact_path_even_and_odd() {
+        assert_eq!(decode_compact_path(&[0x20]).unwrap(), (vec![], true));
+        assert_eq!(decode_compact_path(&[0x3a]).unwrap(), (vec![0xa], true));
+        assert_eq!(decode_compact_path(&[0x00, 0xab]).unwrap(), (vec![0xa, 0xb], false));
+        assert_eq!(decode_compact_path(&[0x1a]).unwrap(), (vec![0xa], false));
+    }
+
+    #[test]
+    fn test_rlp_round_trips_bytes_and_lists() {
+        let bytes_item = rlp_encode_bytes(b"hello world, this is longer than 55 bytes so it exercises the long-string RLP prefix encoding path");
+        assert_eq!(
+            rlp_decode(&bytes_item).unwrap(),
+            RlpItem::Bytes(b"hello world, this is longer than 55 bytes so it exercises the long-string RLP prefix encoding path".to_vec())
+        );
+
+        let list_item = rlp_encode_list(&[rlp_encode_bytes(b"a"), rlp_encode_bytes(b"bc")]);
+        assert_eq!(
+            rlp_decode(&list_item).unwrap(),
+            RlpItem::List(vec![
+                RlpItem::Bytes(b"a".to_vec()),
+                RlpItem::Bytes(b"bc".to_vec())
+            ])
+        );
+    }
 }
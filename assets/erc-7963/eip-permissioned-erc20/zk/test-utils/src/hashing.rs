@@ -0,0 +1,92 @@
+//! Single source of truth for this crate's Keccak-256, so the host
+//! (`crypto_utils`) and the RISC Zero guest agree byte-for-byte with the
+//! EVM's `keccak256` opcode and, transitively, with each other and with
+//! the on-chain `TransferOracle` verifier.
+//!
+//! The guest binary builds as its own no_std zkVM crate and can't
+//! literally depend on this one, so its copy under `methods/guest` has to
+//! be kept in lockstep by hand; this module exists so there is exactly one
+//! canonical implementation to copy from instead of two independently
+//! "placeholder" definitions silently drifting apart.
+
+use tiny_keccak::{Hasher, Keccak};
+
+/// Prefix mixed into a Merkle node hash (but not a leaf hash) before
+/// hashing, so a two-child internal node and a 64-byte leaf preimage can
+/// never collide on the same digest — the RFC 6962 node/leaf
+/// domain-separation convention.
+const NODE_DOMAIN_TAG: u8 = 0x01;
+
+/// Genuine Keccak-256, matching the EVM's `keccak256` opcode byte-for-byte.
+/// This is the original Keccak padding (`0x01` pad byte), NOT FIPS 202
+/// SHA3-256, which pads differently and produces a different digest for
+/// the same input.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Hash two Merkle child nodes into their parent, domain-separated from
+/// [`keccak256`] field/leaf commitments so a node hash can never be
+/// mistaken for (or collide with) a leaf hash over the same bytes.
+pub fn keccak256_node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut combined = Vec::with_capacity(1 + 32 + 32);
+    combined.push(NODE_DOMAIN_TAG);
+    combined.extend_from_slice(left);
+    combined.extend_from_slice(right);
+    keccak256(&combined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Keep this literal byte-for-byte identical to the copy in the guest's
+    // own `test_keccak256_matches_known_empty_string_vector`
+    // (eip-7963/eip-permissioned-erc20/zk/methods/guest/src/lib.rs) — the
+    // guest crate can't depend on this one (it builds as its own no_std
+    // zkVM crate and hand-duplicates `keccak256` instead), so a regression
+    // in either hand-kept copy would otherwise only be caught by the other
+    // crate's own test suite, not this one.
+    #[test]
+    fn test_keccak256_matches_known_empty_string_vector() {
+        let hash = keccak256(b"");
+        assert_eq!(
+            hex::encode(hash),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn test_keccak256_is_deterministic() {
+        let data = b"hello world";
+        assert_eq!(keccak256(data), keccak256(data));
+    }
+
+    #[test]
+    fn test_node_hash_differs_from_plain_concatenation_hash() {
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+
+        let domain_separated = keccak256_node_hash(&left, &right);
+        let mut undifferentiated = Vec::with_capacity(64);
+        undifferentiated.extend_from_slice(&left);
+        undifferentiated.extend_from_slice(&right);
+        let plain = keccak256(&undifferentiated);
+
+        assert_ne!(domain_separated, plain);
+    }
+
+    #[test]
+    fn test_node_hash_is_order_sensitive() {
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+        assert_ne!(
+            keccak256_node_hash(&left, &right),
+            keccak256_node_hash(&right, &left)
+        );
+    }
+}
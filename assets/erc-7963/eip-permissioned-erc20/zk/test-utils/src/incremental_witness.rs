@@ -0,0 +1,184 @@
+//! An incremental Merkle witness modeled on Zcash's `CommitmentTree` /
+//! `IncrementalWitness` / `MerklePath`: it keeps the full tree around so a
+//! single leaf update only rehashes the O(log n) nodes on that leaf's root
+//! path, and every other leaf's authentication path is read straight back
+//! off the (already up to date) cached tree rather than rehashed. This is
+//! what `MerkleTree::new` + `generate_proof` don't give you: rebuilding a
+//! `MerkleTree` from scratch rehashes every internal node even when only
+//! one leaf changed.
+//!
+//! Unlike `MerkleTree`, there is no per-leaf `MerkleProof` cache to
+//! invalidate and patch: `path_for` derives a leaf's siblings directly from
+//! `tree`, which `update_leaf` keeps consistent incrementally, so "patching"
+//! other leaves' proofs is just reading already-correct array entries.
+
+use crate::crypto_utils::HashSuite;
+use crate::merkle_tree::MerkleProof;
+
+#[derive(Debug, Clone)]
+pub struct IncrementalWitness {
+    suite: HashSuite,
+    leaves: Vec<[u8; 32]>,
+    tree: Vec<Vec<[u8; 32]>>,
+}
+
+impl IncrementalWitness {
+    /// Build a witness over `leaves`, using the same unpaired-trailing-node
+    /// promotion policy as `MerkleTree` so roots and proofs agree with it.
+    pub fn build(leaves: Vec<[u8; 32]>, suite: HashSuite) -> Self {
+        let mut tree = vec![leaves.clone()];
+        let mut current_level = leaves.clone();
+
+        while current_level.len() > 1 {
+            let next_level: Vec<[u8; 32]> = current_level
+                .chunks(2)
+                .map(|chunk| {
+                    if chunk.len() == 2 {
+                        suite.node_hash(&chunk[0], &chunk[1])
+                    } else {
+                        chunk[0]
+                    }
+                })
+                .collect();
+            tree.push(next_level.clone());
+            current_level = next_level;
+        }
+
+        Self { suite, leaves, tree }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.last().unwrap()[0]
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Read the authentication path for `position` directly off the cached
+    /// tree. O(log n) array reads, no hashing — always reflects the most
+    /// recent `update_leaf` calls.
+    pub fn path_for(&self, position: usize) -> Result<MerkleProof, String> {
+        if position >= self.leaves.len() {
+            return Err("leaf index out of bounds".to_string());
+        }
+
+        let mut siblings = Vec::new();
+        let mut directions = Vec::new();
+        let mut idx = position;
+
+        for level in 0..self.tree.len() - 1 {
+            let level_size = self.tree[level].len();
+            if idx % 2 == 0 && idx + 1 >= level_size {
+                idx /= 2;
+                continue;
+            }
+
+            let sibling_idx = if idx % 2 == 0 { idx + 1 } else { idx - 1 };
+            siblings.push(self.tree[level][sibling_idx]);
+            directions.push(if idx % 2 == 0 { 0 } else { 1 });
+            idx /= 2;
+        }
+
+        Ok(MerkleProof { siblings, directions })
+    }
+
+    /// Update one leaf's hash in place, rehashing only the nodes on its
+    /// root path — O(log n) calls to `HashSuite::node_hash` — instead of
+    /// rebuilding the whole tree.
+    pub fn update_leaf(&mut self, position: usize, new_hash: [u8; 32]) -> Result<(), String> {
+        if position >= self.leaves.len() {
+            return Err("leaf index out of bounds".to_string());
+        }
+
+        self.leaves[position] = new_hash;
+        self.tree[0][position] = new_hash;
+
+        let mut idx = position;
+        for level in 0..self.tree.len() - 1 {
+            let level_size = self.tree[level].len();
+            let parent_hash = if idx % 2 == 0 && idx + 1 >= level_size {
+                self.tree[level][idx]
+            } else if idx % 2 == 0 {
+                self.suite.node_hash(&self.tree[level][idx], &self.tree[level][idx + 1])
+            } else {
+                self.suite.node_hash(&self.tree[level][idx - 1], &self.tree[level][idx])
+            };
+
+            idx /= 2;
+            self.tree[level + 1][idx] = parent_hash;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle_tree::MerkleTree;
+
+    fn sample_leaves() -> Vec<[u8; 32]> {
+        (0u8..5).map(|tag| HashSuite::default().leaf_hash(&[tag], tag)).collect()
+    }
+
+    #[test]
+    fn test_build_root_folds_leaves_bottom_up() {
+        let leaves = sample_leaves();
+        let witness = IncrementalWitness::build(leaves.clone(), HashSuite::default());
+
+        // Same bottom-up fold `MerkleTree::new_with_suite` uses over
+        // already-hashed leaves, computed independently here as a reference.
+        let mut level = leaves;
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|c| if c.len() == 2 { HashSuite::default().node_hash(&c[0], &c[1]) } else { c[0] })
+                .collect();
+        }
+
+        assert_eq!(witness.root(), level[0]);
+    }
+
+    #[test]
+    fn test_update_leaf_only_touches_root_path() {
+        let leaves = sample_leaves();
+        let mut witness = IncrementalWitness::build(leaves.clone(), HashSuite::default());
+        let old_root = witness.root();
+
+        let new_leaf = HashSuite::default().leaf_hash(b"changed", 99u8);
+        witness.update_leaf(2, new_leaf).unwrap();
+
+        assert_ne!(witness.root(), old_root);
+        assert_eq!(witness.tree[0][2], new_leaf);
+        // Untouched leaves keep their original values.
+        assert_eq!(witness.tree[0][0], leaves[0]);
+        assert_eq!(witness.tree[0][4], leaves[4]);
+    }
+
+    #[test]
+    fn test_path_for_every_leaf_verifies_against_root_after_update() {
+        let leaves = sample_leaves();
+        let mut witness = IncrementalWitness::build(leaves, HashSuite::default());
+
+        let new_leaf = HashSuite::default().leaf_hash(b"changed", 99u8);
+        witness.update_leaf(3, new_leaf).unwrap();
+        let root = witness.root();
+
+        for i in 0..witness.leaf_count() {
+            let proof = witness.path_for(i).unwrap();
+            assert!(MerkleTree::verify_proof_with_suite(
+                &witness.tree[0][i],
+                &proof,
+                &root,
+                HashSuite::default()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_update_leaf_out_of_bounds_errors() {
+        let mut witness = IncrementalWitness::build(sample_leaves(), HashSuite::default());
+        assert!(witness.update_leaf(5, [0u8; 32]).is_err());
+    }
+}
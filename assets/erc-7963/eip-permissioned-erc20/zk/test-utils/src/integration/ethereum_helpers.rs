@@ -1,24 +1,122 @@
 use ethers::{
     prelude::*,
     providers::{Http, Provider},
-    signers::{LocalWallet, Signer},
-    types::{Address, U256, Bytes, TransactionReceipt, Log, H256, TransactionRequest},
+    signers::{coins_bip39::English, HDPath, Ledger, LocalWallet, MnemonicBuilder, Signer, WalletError},
+    types::{
+        transaction::eip2718::TypedTransaction, Address, BlockNumber, Bytes, Eip1559TransactionRequest,
+        Filter, Signature, TransactionReceipt, Log, H256, TransactionRequest, U256, U64,
+    },
     contract::Contract,
-    abi::{Abi, Tokenize},
-    middleware::SignerMiddleware,
+    abi::{Abi, ParamType, Tokenize},
+    middleware::{NonceManagerMiddleware, SignerMiddleware},
+    utils::keccak256,
 };
+use std::collections::VecDeque;
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 use anyhow::{Result, anyhow};
+use futures::future::join_all;
+use futures::stream::{self, Stream};
+use tokio::time::timeout;
+
+/// Fallback priority fee used by `FeeStrategy::Auto` when `eth_feeHistory`
+/// returns no reward data (e.g. a chain/node that doesn't track it).
+const DEFAULT_PRIORITY_FEE_WEI: u64 = 1_000_000_000; // 1 gwei
+
+/// Number of historical blocks `FeeStrategy::Auto` samples via
+/// `eth_feeHistory` to compute a median priority fee.
+const FEE_HISTORY_BLOCK_COUNT: u64 = 10;
+
+/// Percentile of each sampled block's priority fees `FeeStrategy::Auto`
+/// requests from `eth_feeHistory`.
+const FEE_HISTORY_REWARD_PERCENTILE: f64 = 50.0;
+
+/// How a transaction's gas price is determined.
+///
+/// `Legacy` builds a pre-EIP-1559 transaction with a single `gas_price`.
+/// `Eip1559` builds an EIP-1559 typed transaction with caller-supplied fee
+/// caps. `Auto` also builds an EIP-1559 typed transaction, but derives the
+/// fee caps from the node's `eth_feeHistory` instead of requiring the
+/// caller to guess them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeStrategy {
+    Legacy,
+    Eip1559 {
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    },
+    Auto,
+}
+
+impl Default for FeeStrategy {
+    fn default() -> Self {
+        FeeStrategy::Auto
+    }
+}
+
+/// Default cap on transactions a batch submission keeps broadcast-but-
+/// unconfirmed at once.
+const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+/// Default wait before a submitted transaction is rebroadcast with a bumped
+/// fee.
+const DEFAULT_REBROADCAST_AFTER: Duration = Duration::from_secs(30);
+
+/// Factor the bumped fee cap is multiplied by on each rebroadcast.
+const REBROADCAST_FEE_BUMP_PERCENT: u64 = 110; // +10%
+
+/// How `EthereumClient` authenticates and produces signatures for the
+/// issuer's address.
+///
+/// `PrivateKey` and `Mnemonic` both resolve to a `LocalWallet` held in
+/// process memory; `Ledger` instead reaches out to a hardware device over
+/// USB/HID for every signature, so the issuer key never leaves it.
+#[derive(Debug, Clone)]
+pub enum SignerBackend {
+    /// A raw hex private key, parsed directly into a `LocalWallet`.
+    /// Convenient for local development and tests; unsuitable for holding a
+    /// production issuer key.
+    PrivateKey(String),
+    /// A BIP-44 account on a Ledger hardware wallet, reached over its
+    /// native HID/USB transport. `derivation_path` is a full BIP-44 path
+    /// (e.g. `"m/44'/60'/0'/0/0"`); if empty, `index` selects the account
+    /// under Ledger Live's default path instead.
+    Ledger {
+        derivation_path: String,
+        index: u32,
+    },
+    /// A BIP-39 mnemonic phrase, deriving the `index`-th account under the
+    /// standard `m/44'/60'/0'/0/{index}` Ethereum path.
+    Mnemonic { phrase: String, index: u32 },
+}
+
+impl Default for SignerBackend {
+    fn default() -> Self {
+        // Anvil's default funded account, matching the previous
+        // `EthereumConfig::default` private key.
+        SignerBackend::PrivateKey(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string(),
+        )
+    }
+}
 
 /// Configuration for Ethereum connection
 #[derive(Debug, Clone)]
 pub struct EthereumConfig {
     pub rpc_url: String,
     pub chain_id: u64,
-    pub private_key: String,
+    pub signer_backend: SignerBackend,
     pub gas_limit: u64,
     pub gas_price: Option<U256>,
+    pub fee_strategy: FeeStrategy,
+    /// Max number of transactions `TransactionHelper::send_batch_and_confirm`
+    /// keeps broadcast-but-unconfirmed at once, bounding how far ahead of
+    /// confirmation the nonce manager is allowed to race.
+    pub max_in_flight: usize,
+    /// How long `send_batch_and_confirm` waits for a transaction to confirm
+    /// before rebroadcasting it at the same nonce with a bumped fee.
+    pub rebroadcast_after: Duration,
 }
 
 impl Default for EthereumConfig {
@@ -26,18 +124,179 @@ impl Default for EthereumConfig {
         Self {
             rpc_url: "http://localhost:8545".to_string(), // Anvil default
             chain_id: 31337, // Anvil default chain ID
-            private_key: "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string(), // Anvil default key
+            signer_backend: SignerBackend::default(),
             gas_limit: 3_000_000,
             gas_price: None, // Use network default
+            fee_strategy: FeeStrategy::default(),
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            rebroadcast_after: DEFAULT_REBROADCAST_AFTER,
+        }
+    }
+}
+
+/// Errors from an `AnySigner`, distinguishing a local-wallet signing error
+/// from a Ledger hardware-wallet failure (device not connected/unlocked,
+/// wrong app open, or the user rejecting the signing request on-device).
+#[derive(Debug)]
+pub enum AnySignerError {
+    Local(WalletError),
+    Ledger(String),
+}
+
+impl fmt::Display for AnySignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnySignerError::Local(e) => write!(f, "local wallet signer error: {}", e),
+            AnySignerError::Ledger(msg) => write!(f, "Ledger hardware wallet error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AnySignerError {}
+
+/// Signer-agnostic handle `EthereumClient` signs through, so the rest of
+/// `EthereumClient`/`TransactionHelper` doesn't care whether the issuer key
+/// lives in a config file or on a hardware device.
+///
+/// `ethers::signers::Signer` carries an associated `Error` type and two
+/// methods generic over their argument (`sign_message`, `sign_typed_data`),
+/// so it isn't object-safe — `Arc<dyn Signer>` doesn't compile. Enum
+/// dispatch over the concrete signer kinds gets the same "signer-agnostic
+/// call site" property without that restriction, mirroring how
+/// `FeeStrategy`/`BackendKind` dispatch over their variants elsewhere in
+/// this crate.
+#[derive(Debug, Clone)]
+pub enum AnySigner {
+    Local(LocalWallet),
+    Ledger(Arc<Ledger>),
+}
+
+#[async_trait::async_trait]
+impl Signer for AnySigner {
+    type Error = AnySignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            AnySigner::Local(wallet) => wallet
+                .sign_message(message)
+                .await
+                .map_err(AnySignerError::Local),
+            AnySigner::Ledger(ledger) => ledger
+                .sign_message(message)
+                .await
+                .map_err(|e| AnySignerError::Ledger(e.to_string())),
+        }
+    }
+
+    async fn sign_transaction(&self, message: &TypedTransaction) -> Result<Signature, Self::Error> {
+        match self {
+            AnySigner::Local(wallet) => wallet
+                .sign_transaction(message)
+                .await
+                .map_err(AnySignerError::Local),
+            AnySigner::Ledger(ledger) => ledger.sign_transaction(message).await.map_err(|e| {
+                AnySignerError::Ledger(format!(
+                    "transaction signing failed or was rejected on-device: {}",
+                    e
+                ))
+            }),
+        }
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> Result<Signature, Self::Error> {
+        match self {
+            AnySigner::Local(wallet) => wallet
+                .sign_typed_data(payload)
+                .await
+                .map_err(AnySignerError::Local),
+            AnySigner::Ledger(ledger) => ledger
+                .sign_typed_data(payload)
+                .await
+                .map_err(|e| AnySignerError::Ledger(e.to_string())),
+        }
+    }
+
+    fn address(&self) -> Address {
+        match self {
+            AnySigner::Local(wallet) => wallet.address(),
+            AnySigner::Ledger(ledger) => ledger.address(),
+        }
+    }
+
+    fn chain_id(&self) -> u64 {
+        match self {
+            AnySigner::Local(wallet) => wallet.chain_id(),
+            AnySigner::Ledger(ledger) => ledger.chain_id(),
+        }
+    }
+
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        match self {
+            AnySigner::Local(wallet) => AnySigner::Local(wallet.with_chain_id(chain_id)),
+            // The Ledger device signs with its own chain ID understanding
+            // (EIP-155 `v` is derived per-transaction); nothing to update here.
+            AnySigner::Ledger(ledger) => AnySigner::Ledger(ledger),
+        }
+    }
+}
+
+/// Build the concrete signer `config.signer_backend` describes.
+async fn build_signer(backend: &SignerBackend, chain_id: u64) -> Result<AnySigner> {
+    match backend {
+        SignerBackend::PrivateKey(key) => {
+            let wallet = key
+                .parse::<LocalWallet>()
+                .map_err(|e| anyhow!("invalid private key: {}", e))?
+                .with_chain_id(chain_id);
+            Ok(AnySigner::Local(wallet))
+        }
+        SignerBackend::Mnemonic { phrase, index } => {
+            let wallet = MnemonicBuilder::<English>::default()
+                .phrase(phrase.as_str())
+                .index(*index)
+                .map_err(|e| anyhow!("invalid mnemonic derivation index {}: {}", index, e))?
+                .build()
+                .map_err(|e| anyhow!("failed to derive wallet from mnemonic: {}", e))?
+                .with_chain_id(chain_id);
+            Ok(AnySigner::Local(wallet))
+        }
+        SignerBackend::Ledger {
+            derivation_path,
+            index,
+        } => {
+            let hd_path = if derivation_path.is_empty() {
+                HDPath::LedgerLive(*index)
+            } else {
+                HDPath::Other(derivation_path.clone())
+            };
+            let ledger = Ledger::new(hd_path, chain_id).await.map_err(|e| {
+                anyhow!(
+                    "failed to connect to Ledger device: {} (check it is connected, unlocked, \
+                     and the Ethereum app is open)",
+                    e
+                )
+            })?;
+            Ok(AnySigner::Ledger(Arc::new(ledger)))
         }
     }
 }
 
+/// `SignerMiddleware` wrapped in a `NonceManagerMiddleware` so concurrent
+/// submissions hand out monotonic nonces under a mutex instead of each
+/// independently reading `eth_getTransactionCount` and racing.
+pub type SignerStack = NonceManagerMiddleware<SignerMiddleware<Arc<Provider<Http>>, AnySigner>>;
+
 /// Ethereum client wrapper for contract interactions
 pub struct EthereumClient {
     pub provider: Arc<Provider<Http>>,
-    pub wallet: LocalWallet,
-    pub signer: Arc<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>>,
+    pub signer_address: Address,
+    pub signer: Arc<SignerStack>,
     pub config: EthereumConfig,
 }
 
@@ -46,26 +305,29 @@ impl EthereumClient {
     pub async fn new(config: EthereumConfig) -> Result<Self> {
         let provider = Provider::<Http>::try_from(&config.rpc_url)?
             .interval(Duration::from_millis(100));
-        
-        let wallet = config.private_key.parse::<LocalWallet>()?
-            .with_chain_id(config.chain_id);
-        
         let provider = Arc::new(provider);
-        let signer = Arc::new(SignerMiddleware::new(provider.clone(), wallet.clone()));
-        
+
+        let signer = build_signer(&config.signer_backend, config.chain_id).await?;
+        let signer_address = signer.address();
+        let signer_middleware = SignerMiddleware::new(provider.clone(), signer);
+        // Caches the account's next nonce and hands out monotonic values
+        // under a mutex; resyncs from `eth_getTransactionCount` whenever the
+        // node reports the cached nonce as stale (e.g. nonce-too-low).
+        let signer = Arc::new(NonceManagerMiddleware::new(signer_middleware, signer_address));
+
         Ok(Self {
             provider,
-            wallet,
+            signer_address,
             signer,
             config,
         })
     }
-    
+
     /// Get the client's address
     pub fn address(&self) -> Address {
-        self.wallet.address()
+        self.signer_address
     }
-    
+
     /// Get current block number
     pub async fn block_number(&self) -> Result<U256> {
         let block_num = self.provider.get_block_number().await?;
@@ -83,26 +345,111 @@ impl EthereumClient {
         bytecode: Bytes,
         constructor_args: Option<Bytes>,
     ) -> Result<Address> {
-        let mut deployment_tx = TransactionRequest::new()
-            .data(bytecode)
-            .gas(self.config.gas_limit);
-        
-        if let Some(args) = constructor_args {
-            deployment_tx = deployment_tx.data(args);
-        }
-        
-        if let Some(gas_price) = self.config.gas_price {
-            deployment_tx = deployment_tx.gas_price(gas_price);
-        }
-        
+        let data = match constructor_args {
+            // Constructor args are appended after the init bytecode, matching
+            // the previous two-step `.data(bytecode).data(args)` behavior.
+            Some(args) => Bytes::from([bytecode.to_vec(), args.to_vec()].concat()),
+            None => bytecode,
+        };
+
+        let deployment_tx = self
+            .build_typed_transaction(None, data, self.config.gas_limit.into())
+            .await?;
+
         let pending_tx = self.signer.send_transaction(deployment_tx, None).await?;
         let receipt = pending_tx.await?.ok_or_else(|| anyhow!("Transaction failed"))?;
-        
+
         receipt.contract_address.ok_or_else(|| anyhow!("No contract address in receipt"))
     }
+
+    /// Build a `TypedTransaction` under `self.config.fee_strategy`: a legacy
+    /// transaction with a single `gas_price`, or an EIP-1559 transaction with
+    /// either caller-supplied or `eth_feeHistory`-derived fee caps.
+    pub async fn build_typed_transaction(
+        &self,
+        to: Option<Address>,
+        data: Bytes,
+        gas: U256,
+    ) -> Result<TypedTransaction> {
+        match self.config.fee_strategy {
+            FeeStrategy::Legacy => {
+                let mut tx = TransactionRequest::new().data(data).gas(gas);
+                if let Some(to) = to {
+                    tx = tx.to(to);
+                }
+                if let Some(gas_price) = self.config.gas_price {
+                    tx = tx.gas_price(gas_price);
+                }
+                Ok(tx.into())
+            }
+            FeeStrategy::Eip1559 { .. } | FeeStrategy::Auto => {
+                let (max_fee_per_gas, max_priority_fee_per_gas) = self.resolve_eip1559_fees().await?;
+                let mut tx = Eip1559TransactionRequest::new()
+                    .data(data)
+                    .gas(gas)
+                    .max_fee_per_gas(max_fee_per_gas)
+                    .max_priority_fee_per_gas(max_priority_fee_per_gas)
+                    .chain_id(self.config.chain_id);
+                if let Some(to) = to {
+                    tx = tx.to(to);
+                }
+                Ok(tx.into())
+            }
+        }
+    }
+
+    /// Resolve `self.config.fee_strategy` into concrete EIP-1559
+    /// `(max_fee_per_gas, max_priority_fee_per_gas)` caps. `Eip1559` returns
+    /// its caller-supplied values unchanged; `Auto` queries `eth_feeHistory`
+    /// for the last `FEE_HISTORY_BLOCK_COUNT` blocks' 50th-percentile
+    /// priority fee, takes their median, and sets
+    /// `max_fee = 2 * latest_base_fee + median_priority_fee` so the cap
+    /// tolerates a few consecutive base-fee increases.
+    async fn resolve_eip1559_fees(&self) -> Result<(U256, U256)> {
+        match self.config.fee_strategy {
+            FeeStrategy::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => Ok((max_fee_per_gas, max_priority_fee_per_gas)),
+            FeeStrategy::Auto => {
+                let history = self
+                    .provider
+                    .fee_history(
+                        FEE_HISTORY_BLOCK_COUNT,
+                        BlockNumber::Latest,
+                        &[FEE_HISTORY_REWARD_PERCENTILE],
+                    )
+                    .await?;
+
+                let base_fee = *history
+                    .base_fee_per_gas
+                    .last()
+                    .ok_or_else(|| anyhow!("eth_feeHistory returned no base fees"))?;
+
+                let mut priority_fees: Vec<U256> = history
+                    .reward
+                    .iter()
+                    .filter_map(|percentiles| percentiles.first().copied())
+                    .collect();
+                priority_fees.sort();
+
+                let median_priority_fee = if priority_fees.is_empty() {
+                    U256::from(DEFAULT_PRIORITY_FEE_WEI)
+                } else {
+                    priority_fees[priority_fees.len() / 2]
+                };
+
+                let max_fee_per_gas = base_fee * 2 + median_priority_fee;
+                Ok((max_fee_per_gas, median_priority_fee))
+            }
+            FeeStrategy::Legacy => Err(anyhow!(
+                "resolve_eip1559_fees called with FeeStrategy::Legacy"
+            )),
+        }
+    }
     
     /// Create a contract instance
-    pub fn contract<T: Tokenize>(&self, address: Address, abi: Abi) -> Contract<Arc<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>>> {
+    pub fn contract<T: Tokenize>(&self, address: Address, abi: Abi) -> Contract<Arc<SignerStack>> {
         Contract::new(address, abi, self.signer.clone())
     }
     
@@ -115,24 +462,144 @@ impl EthereumClient {
         Ok(receipt)
     }
     
-    /// Estimate gas for a transaction
-    pub async fn estimate_gas(&self, tx: &TransactionRequest) -> Result<U256> {
-        // Convert TransactionRequest to TypedTransaction for estimate_gas
-        let typed_tx = tx.clone().into();
+    /// Estimate gas for a call to `to` carrying `data`, built as a typed
+    /// transaction under `self.config.fee_strategy` so the estimate reflects
+    /// the same transaction shape (legacy vs. EIP-1559) that would actually
+    /// be sent.
+    pub async fn estimate_gas(&self, to: Option<Address>, data: Bytes) -> Result<U256> {
+        let typed_tx = self
+            .build_typed_transaction(to, data, self.config.gas_limit.into())
+            .await?;
         Ok(self.provider.estimate_gas(&typed_tx, None).await?)
     }
 }
 
+/// Bump a built transaction's fee fields in place by
+/// [`REBROADCAST_FEE_BUMP_PERCENT`], preserving its nonce so a rebroadcast
+/// replaces rather than races the original.
+fn bump_transaction_fee(tx: &mut TypedTransaction) {
+    match tx {
+        TypedTransaction::Legacy(inner) => {
+            if let Some(gas_price) = inner.gas_price {
+                inner.gas_price = Some(gas_price * REBROADCAST_FEE_BUMP_PERCENT / 100);
+            }
+        }
+        TypedTransaction::Eip1559(inner) => {
+            if let Some(max_fee_per_gas) = inner.max_fee_per_gas {
+                inner.max_fee_per_gas = Some(max_fee_per_gas * REBROADCAST_FEE_BUMP_PERCENT / 100);
+            }
+            if let Some(max_priority_fee_per_gas) = inner.max_priority_fee_per_gas {
+                inner.max_priority_fee_per_gas =
+                    Some(max_priority_fee_per_gas * REBROADCAST_FEE_BUMP_PERCENT / 100);
+            }
+        }
+        TypedTransaction::Eip2930(inner) => {
+            if let Some(gas_price) = inner.tx.gas_price {
+                inner.tx.gas_price = Some(gas_price * REBROADCAST_FEE_BUMP_PERCENT / 100);
+            }
+        }
+    }
+}
+
+/// Byte prefix the CREATE2 address formula prepends to
+/// `deployer ++ salt ++ keccak256(init_code)` before hashing, per EIP-1014.
+const CREATE2_ADDRESS_PREFIX: u8 = 0xff;
+
 /// Contract deployment helper
 pub struct ContractDeployer {
     client: Arc<EthereumClient>,
+    /// Address of the minimal CREATE2 deployer/factory contract that
+    /// `deploy_contract_create2` routes through. CREATE2 addresses depend on
+    /// the address of the contract executing the opcode, not the sender's
+    /// EOA, so every chain this is deployed to must use the same factory
+    /// address (e.g. via a canonical, nonce-independent factory deployment)
+    /// for `compute_create2_address` to stay chain-independent.
+    factory_address: Address,
 }
 
 impl ContractDeployer {
-    pub fn new(client: Arc<EthereumClient>) -> Self {
-        Self { client }
+    pub fn new(client: Arc<EthereumClient>, factory_address: Address) -> Self {
+        Self {
+            client,
+            factory_address,
+        }
     }
-    
+
+    /// Predict the address a CREATE2 deployment through `factory_address`
+    /// will produce, without sending a transaction:
+    /// `keccak256(0xff ++ factory_address ++ salt ++ keccak256(init_code))[12..32]`,
+    /// where `init_code` is `bytecode ++ abi_encoded_constructor_args`.
+    pub fn compute_create2_address(
+        &self,
+        salt: [u8; 32],
+        bytecode: &Bytes,
+        constructor_args: Option<&Bytes>,
+    ) -> Address {
+        let init_code = match constructor_args {
+            Some(args) => [bytecode.to_vec(), args.to_vec()].concat(),
+            None => bytecode.to_vec(),
+        };
+        let init_code_hash = keccak256(&init_code);
+
+        let mut preimage = Vec::with_capacity(1 + 20 + 32 + 32);
+        preimage.push(CREATE2_ADDRESS_PREFIX);
+        preimage.extend_from_slice(self.factory_address.as_bytes());
+        preimage.extend_from_slice(&salt);
+        preimage.extend_from_slice(&init_code_hash);
+
+        Address::from_slice(&keccak256(&preimage)[12..32])
+    }
+
+    /// Deploy `bytecode` (plus optional ABI-encoded constructor args) via
+    /// CREATE2 through the minimal deployer/factory contract at
+    /// `factory_address`, returning the same address
+    /// [`compute_create2_address`] would have predicted for this `salt`.
+    pub async fn deploy_contract_create2(
+        &self,
+        salt: [u8; 32],
+        bytecode: Bytes,
+        constructor_args: Option<Bytes>,
+    ) -> Result<Address> {
+        let predicted_address =
+            self.compute_create2_address(salt, &bytecode, constructor_args.as_ref());
+
+        let init_code = match &constructor_args {
+            Some(args) => [bytecode.to_vec(), args.to_vec()].concat(),
+            None => bytecode.to_vec(),
+        };
+
+        // `deploy(bytes32 salt, bytes memory initCode)` selector on the
+        // minimal deployer/factory contract.
+        let call_data = ethers::abi::encode(&[
+            ethers::abi::Token::FixedBytes(salt.to_vec()),
+            ethers::abi::Token::Bytes(init_code),
+        ]);
+        let mut data = keccak256("deploy(bytes32,bytes)".as_bytes())[0..4].to_vec();
+        data.extend(call_data);
+
+        let tx = self
+            .client
+            .build_typed_transaction(
+                Some(self.factory_address),
+                Bytes::from(data),
+                self.client.config.gas_limit.into(),
+            )
+            .await?;
+        let pending_tx = self.client.signer.send_transaction(tx, None).await?;
+        let receipt = pending_tx
+            .await?
+            .ok_or_else(|| anyhow!("CREATE2 deployment transaction failed"))?;
+
+        if receipt.status != Some(1.into()) {
+            return Err(anyhow!(
+                "CREATE2 deployment through factory {:?} reverted",
+                self.factory_address
+            ));
+        }
+
+        Ok(predicted_address)
+    }
+
     /// Deploy RiscZeroVerifier contract
     pub async fn deploy_risc_zero_verifier(&self) -> Result<Address> {
         // This would contain the actual bytecode for RiscZeroVerifier
@@ -180,6 +647,10 @@ impl ContractDeployer {
     }
 }
 
+/// Max times `send_and_confirm_with_rebroadcast` will bump the fee and
+/// resubmit before giving up on a single transaction.
+const MAX_REBROADCAST_ATTEMPTS: u32 = 5;
+
 /// Transaction helper utilities
 pub struct TransactionHelper {
     client: Arc<EthereumClient>,
@@ -189,14 +660,81 @@ impl TransactionHelper {
     pub fn new(client: Arc<EthereumClient>) -> Self {
         Self { client }
     }
-    
-    /// Send a transaction and wait for confirmation
-    pub async fn send_and_confirm(&self, tx: TransactionRequest) -> Result<TransactionReceipt> {
+
+    /// Build a typed transaction to `to` carrying `data` under the client's
+    /// `FeeStrategy`, send it, and wait for confirmation.
+    pub async fn send_and_confirm(&self, to: Option<Address>, data: Bytes) -> Result<TransactionReceipt> {
+        let tx = self
+            .client
+            .build_typed_transaction(to, data, self.client.config.gas_limit.into())
+            .await?;
         let pending_tx = self.client.signer.send_transaction(tx, None).await?;
         let receipt = pending_tx.await?.ok_or_else(|| anyhow!("Transaction failed"))?;
         Ok(receipt)
     }
-    
+
+    /// Like [`send_and_confirm`](Self::send_and_confirm), but if the
+    /// transaction hasn't confirmed within `self.client.config
+    /// .rebroadcast_after`, resubmit it at the same nonce with its fee
+    /// bumped by [`REBROADCAST_FEE_BUMP_PERCENT`], up to
+    /// [`MAX_REBROADCAST_ATTEMPTS`] times.
+    async fn send_and_confirm_with_rebroadcast(
+        &self,
+        to: Option<Address>,
+        data: Bytes,
+    ) -> Result<TransactionReceipt> {
+        let mut tx = self
+            .client
+            .build_typed_transaction(to, data, self.client.config.gas_limit.into())
+            .await?;
+        // Fix the nonce once, before the first send, so every rebroadcast
+        // reuses it instead of the nonce manager handing out a fresh one.
+        self.client.signer.fill_transaction(&mut tx, None).await?;
+
+        for attempt in 0..=MAX_REBROADCAST_ATTEMPTS {
+            let pending_tx = self.client.signer.send_transaction(tx.clone(), None).await?;
+            match timeout(self.client.config.rebroadcast_after, pending_tx).await {
+                Ok(result) => {
+                    return result?.ok_or_else(|| anyhow!("Transaction failed"));
+                }
+                Err(_) if attempt < MAX_REBROADCAST_ATTEMPTS => {
+                    bump_transaction_fee(&mut tx);
+                }
+                Err(_) => {
+                    return Err(anyhow!(
+                        "transaction did not confirm after {} rebroadcast attempt(s)",
+                        MAX_REBROADCAST_ATTEMPTS
+                    ));
+                }
+            }
+        }
+
+        unreachable!("loop always returns by its last iteration")
+    }
+
+    /// Submit a batch of `(to, data)` calls concurrently, relying on the
+    /// client's `NonceManagerMiddleware` to hand out monotonic nonces so
+    /// submissions don't race each other, and each rebroadcasting under
+    /// [`send_and_confirm_with_rebroadcast`] if it stalls. At most
+    /// `self.client.config.max_in_flight` transactions are
+    /// broadcast-but-unconfirmed at once.
+    pub async fn send_batch_and_confirm(
+        &self,
+        txs: Vec<(Option<Address>, Bytes)>,
+    ) -> Result<Vec<TransactionReceipt>> {
+        let mut receipts = Vec::with_capacity(txs.len());
+        for chunk in txs.chunks(self.client.config.max_in_flight) {
+            let pending = chunk
+                .iter()
+                .cloned()
+                .map(|(to, data)| self.send_and_confirm_with_rebroadcast(to, data));
+            for result in join_all(pending).await {
+                receipts.push(result?);
+            }
+        }
+        Ok(receipts)
+    }
+
     /// Get transaction gas usage
     pub fn get_gas_used(receipt: &TransactionReceipt) -> U256 {
         receipt.gas_used.unwrap_or_default()
@@ -208,6 +746,92 @@ impl TransactionHelper {
     }
 }
 
+/// Canonical Solidity event signatures this module filters and decodes.
+/// Each log's `topics[0]` is `keccak256` of its event's signature string.
+const TRANSFER_APPROVED_SIGNATURE: &str = "TransferApproved(bytes32,address,address,uint256,uint256)";
+const APPROVAL_CONSUMED_SIGNATURE: &str = "ApprovalConsumed(bytes32,address)";
+const ERC20_TRANSFER_SIGNATURE: &str = "Transfer(address,address,uint256)";
+
+fn event_topic0(signature: &str) -> H256 {
+    H256::from(keccak256(signature.as_bytes()))
+}
+
+/// Emitted by `TransferOracle` once a ZK proof for a payment instruction
+/// has verified successfully and the transfer is approved for settlement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferApproved {
+    pub approval_id: H256,
+    pub from: Address,
+    pub to: Address,
+    pub amount: U256,
+    pub expiry: U256,
+    pub block_number: U64,
+    pub transaction_hash: H256,
+}
+
+/// Emitted by `TransferOracle` once an approval has been consumed to
+/// execute the underlying ERC-20 transfer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalConsumed {
+    pub approval_id: H256,
+    pub consumer: Address,
+    pub block_number: U64,
+    pub transaction_hash: H256,
+}
+
+fn decode_transfer_approved(log: &Log) -> Result<TransferApproved> {
+    if log.topics.len() != 4 {
+        return Err(anyhow!(
+            "TransferApproved log has {} topics, expected 4",
+            log.topics.len()
+        ));
+    }
+    let decoded = ethers::abi::decode(&[ParamType::Uint(256), ParamType::Uint(256)], &log.data)
+        .map_err(|e| anyhow!("failed to decode TransferApproved data: {}", e))?;
+    let amount = decoded[0]
+        .clone()
+        .into_uint()
+        .ok_or_else(|| anyhow!("TransferApproved amount is not a uint"))?;
+    let expiry = decoded[1]
+        .clone()
+        .into_uint()
+        .ok_or_else(|| anyhow!("TransferApproved expiry is not a uint"))?;
+
+    Ok(TransferApproved {
+        approval_id: log.topics[1],
+        from: Address::from(log.topics[2]),
+        to: Address::from(log.topics[3]),
+        amount,
+        expiry,
+        block_number: log
+            .block_number
+            .ok_or_else(|| anyhow!("TransferApproved log missing block number"))?,
+        transaction_hash: log
+            .transaction_hash
+            .ok_or_else(|| anyhow!("TransferApproved log missing transaction hash"))?,
+    })
+}
+
+fn decode_approval_consumed(log: &Log) -> Result<ApprovalConsumed> {
+    if log.topics.len() != 3 {
+        return Err(anyhow!(
+            "ApprovalConsumed log has {} topics, expected 3",
+            log.topics.len()
+        ));
+    }
+
+    Ok(ApprovalConsumed {
+        approval_id: log.topics[1],
+        consumer: Address::from(log.topics[2]),
+        block_number: log
+            .block_number
+            .ok_or_else(|| anyhow!("ApprovalConsumed log missing block number"))?,
+        transaction_hash: log
+            .transaction_hash
+            .ok_or_else(|| anyhow!("ApprovalConsumed log missing transaction hash"))?,
+    })
+}
+
 /// Event monitoring utilities
 pub struct EventMonitor {
     client: Arc<EthereumClient>,
@@ -217,27 +841,137 @@ impl EventMonitor {
     pub fn new(client: Arc<EthereumClient>) -> Self {
         Self { client }
     }
-    
-    /// Monitor for TransferApproved events
+
+    /// Fetch and decode every `TransferApproved` log emitted by
+    /// `contract_address` from `from_block` (default: genesis) onward.
     pub async fn wait_for_transfer_approved(
         &self,
         contract_address: Address,
         from_block: Option<U256>,
-    ) -> Result<Vec<Log>> {
-        // This would implement actual event filtering
-        // For now, return empty vector
-        Ok(vec![])
+    ) -> Result<Vec<TransferApproved>> {
+        let filter = Filter::new()
+            .address(contract_address)
+            .topic0(event_topic0(TRANSFER_APPROVED_SIGNATURE))
+            .from_block(from_block.map(|b| b.as_u64()).unwrap_or(0));
+        let logs = self.client.provider.get_logs(&filter).await?;
+        logs.iter().map(decode_transfer_approved).collect()
     }
-    
-    /// Monitor for ApprovalConsumed events
+
+    /// Fetch and decode every `ApprovalConsumed` log emitted by
+    /// `contract_address`, keeping only the ones that cross-check against a
+    /// real ERC-20 `Transfer` log from `token_address` in the same
+    /// transaction.
+    ///
+    /// This mirrors the cross-chain bridge pattern of never settling on a
+    /// single emitter's event in isolation: a compromised or spoofed
+    /// `TransferOracle` could emit `ApprovalConsumed` without the token
+    /// balance actually having moved, so every consumption is verified
+    /// against the token contract's own transfer log before being reported
+    /// as genuinely settled.
     pub async fn wait_for_approval_consumed(
         &self,
         contract_address: Address,
+        token_address: Address,
         from_block: Option<U256>,
-    ) -> Result<Vec<Log>> {
-        // This would implement actual event filtering
-        // For now, return empty vector
-        Ok(vec![])
+    ) -> Result<Vec<ApprovalConsumed>> {
+        let filter = Filter::new()
+            .address(contract_address)
+            .topic0(event_topic0(APPROVAL_CONSUMED_SIGNATURE))
+            .from_block(from_block.map(|b| b.as_u64()).unwrap_or(0));
+        let logs = self.client.provider.get_logs(&filter).await?;
+
+        let mut settled = Vec::with_capacity(logs.len());
+        for log in &logs {
+            let consumed = decode_approval_consumed(log)?;
+            if self
+                .transaction_has_token_transfer(consumed.transaction_hash, token_address)
+                .await?
+            {
+                settled.push(consumed);
+            }
+        }
+        Ok(settled)
+    }
+
+    /// Whether `tx_hash`'s receipt contains at least one ERC-20 `Transfer`
+    /// log emitted by `token_address`.
+    async fn transaction_has_token_transfer(
+        &self,
+        tx_hash: H256,
+        token_address: Address,
+    ) -> Result<bool> {
+        let receipt = self
+            .client
+            .provider
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .ok_or_else(|| anyhow!("transaction {:?} not found", tx_hash))?;
+        let transfer_topic0 = event_topic0(ERC20_TRANSFER_SIGNATURE);
+        Ok(receipt.logs.iter().any(|log| {
+            log.address == token_address && log.topics.first() == Some(&transfer_topic0)
+        }))
+    }
+
+    /// Stream newly observed `TransferApproved` events from `from_block`
+    /// onward, polling `get_logs` over the new-block range every
+    /// `poll_interval` instead of requiring a websocket subscription.
+    pub fn subscribe_transfer_approved(
+        &self,
+        contract_address: Address,
+        from_block: U64,
+        poll_interval: Duration,
+    ) -> impl Stream<Item = Result<TransferApproved>> + '_ {
+        struct State<'a> {
+            monitor: &'a EventMonitor,
+            contract_address: Address,
+            next_block: U64,
+            pending: VecDeque<TransferApproved>,
+        }
+
+        stream::unfold(
+            State {
+                monitor: self,
+                contract_address,
+                next_block: from_block,
+                pending: VecDeque::new(),
+            },
+            |mut state| async move {
+                loop {
+                    if let Some(event) = state.pending.pop_front() {
+                        return Some((Ok(event), state));
+                    }
+
+                    let latest = match state.monitor.client.provider.get_block_number().await {
+                        Ok(b) => b,
+                        Err(e) => {
+                            return Some((Err(anyhow!("failed to fetch latest block: {}", e)), state))
+                        }
+                    };
+                    if latest < state.next_block {
+                        tokio::time::sleep(poll_interval).await;
+                        continue;
+                    }
+
+                    let filter = Filter::new()
+                        .address(state.contract_address)
+                        .topic0(event_topic0(TRANSFER_APPROVED_SIGNATURE))
+                        .from_block(state.next_block)
+                        .to_block(latest);
+                    match state.monitor.client.provider.get_logs(&filter).await {
+                        Ok(logs) => {
+                            for log in &logs {
+                                match decode_transfer_approved(log) {
+                                    Ok(event) => state.pending.push_back(event),
+                                    Err(e) => return Some((Err(e), state)),
+                                }
+                            }
+                            state.next_block = latest + 1;
+                        }
+                        Err(e) => return Some((Err(anyhow!("get_logs failed: {}", e)), state)),
+                    }
+                }
+            },
+        )
     }
 }
 
@@ -251,8 +985,123 @@ mod tests {
         assert_eq!(config.rpc_url, "http://localhost:8545");
         assert_eq!(config.chain_id, 31337);
         assert_eq!(config.gas_limit, 3_000_000);
+        assert_eq!(config.fee_strategy, FeeStrategy::Auto);
+        assert_eq!(config.max_in_flight, DEFAULT_MAX_IN_FLIGHT);
+        assert_eq!(config.rebroadcast_after, DEFAULT_REBROADCAST_AFTER);
     }
-    
+
+    #[test]
+    fn test_fee_strategy_default_is_auto() {
+        assert_eq!(FeeStrategy::default(), FeeStrategy::Auto);
+    }
+
+    #[tokio::test]
+    async fn test_private_key_signer_backend_produces_matching_address() {
+        let config = EthereumConfig::default();
+        let client = EthereumClient::new(config).await.unwrap();
+        // Anvil's well-known first funded account for its default private key.
+        assert_eq!(
+            format!("{:?}", client.address()).to_lowercase(),
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_private_key_is_rejected() {
+        let mut config = EthereumConfig::default();
+        config.signer_backend = SignerBackend::PrivateKey("not-a-hex-key".to_string());
+        let result = EthereumClient::new(config).await;
+        assert!(result.is_err());
+    }
+
+    fn sample_log(topics: Vec<H256>, data: Vec<u8>) -> Log {
+        Log {
+            address: Address::zero(),
+            topics,
+            data: data.into(),
+            block_hash: None,
+            block_number: Some(U64::from(100)),
+            transaction_hash: Some(H256::repeat_byte(0xab)),
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: Some(false),
+        }
+    }
+
+    #[test]
+    fn test_decode_transfer_approved() {
+        let approval_id = H256::repeat_byte(0x11);
+        let from = Address::from_low_u64_be(1);
+        let to = Address::from_low_u64_be(2);
+        let amount = U256::from(1_000u64);
+        let expiry = U256::from(2_000u64);
+        let data = ethers::abi::encode(&[
+            ethers::abi::Token::Uint(amount),
+            ethers::abi::Token::Uint(expiry),
+        ]);
+
+        let log = sample_log(
+            vec![
+                event_topic0(TRANSFER_APPROVED_SIGNATURE),
+                approval_id,
+                H256::from(from),
+                H256::from(to),
+            ],
+            data,
+        );
+
+        let decoded = decode_transfer_approved(&log).unwrap();
+        assert_eq!(decoded.approval_id, approval_id);
+        assert_eq!(decoded.from, from);
+        assert_eq!(decoded.to, to);
+        assert_eq!(decoded.amount, amount);
+        assert_eq!(decoded.expiry, expiry);
+    }
+
+    #[test]
+    fn test_decode_transfer_approved_rejects_wrong_topic_count() {
+        let log = sample_log(vec![event_topic0(TRANSFER_APPROVED_SIGNATURE)], vec![]);
+        assert!(decode_transfer_approved(&log).is_err());
+    }
+
+    #[test]
+    fn test_decode_approval_consumed() {
+        let approval_id = H256::repeat_byte(0x22);
+        let consumer = Address::from_low_u64_be(3);
+        let log = sample_log(
+            vec![
+                event_topic0(APPROVAL_CONSUMED_SIGNATURE),
+                approval_id,
+                H256::from(consumer),
+            ],
+            vec![],
+        );
+
+        let decoded = decode_approval_consumed(&log).unwrap();
+        assert_eq!(decoded.approval_id, approval_id);
+        assert_eq!(decoded.consumer, consumer);
+    }
+
+    #[tokio::test]
+    async fn test_create2_address_is_deterministic_and_salt_dependent() {
+        let client = Arc::new(EthereumClient::new(EthereumConfig::default()).await.unwrap());
+        let factory_address = Address::from_low_u64_be(0x1234);
+        let deployer = ContractDeployer::new(client, factory_address);
+
+        let bytecode = Bytes::from_static(&[0x60, 0x80, 0x60, 0x40]);
+        let salt_a = [1u8; 32];
+        let salt_b = [2u8; 32];
+
+        let address_a1 = deployer.compute_create2_address(salt_a, &bytecode, None);
+        let address_a2 = deployer.compute_create2_address(salt_a, &bytecode, None);
+        let address_b = deployer.compute_create2_address(salt_b, &bytecode, None);
+
+        assert_eq!(address_a1, address_a2);
+        assert_ne!(address_a1, address_b);
+    }
+
     #[tokio::test]
     #[ignore] // Requires running Ethereum node
     async fn test_ethereum_client_creation() {
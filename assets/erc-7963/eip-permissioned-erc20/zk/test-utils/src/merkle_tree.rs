@@ -1,4 +1,18 @@
-use crate::crypto_utils::{compute_leaf_hash, poseidon_hash};
+use crate::crypto_utils::{Hasher, HashSuite};
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+/// Node hashing for `MerkleTree::new_domain_separated`: Poseidon suites
+/// route through `poseidon_node_hash_domain_separated`, `KeccakOnly` keeps
+/// its own already-domain-separated `node_hash` unchanged.
+fn domain_separated_node_hash(suite: HashSuite, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    match suite {
+        HashSuite::PoseidonKeccak | HashSuite::PoseidonSha256 => {
+            crate::poseidon::poseidon_node_hash_domain_separated(left, right)
+        }
+        HashSuite::KeccakOnly => suite.node_hash(left, right),
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct MerkleProof {
@@ -13,26 +27,36 @@ pub struct MerkleTree {
 }
 
 impl MerkleTree {
-    /// Create a new Merkle tree from leaf data
+    /// Create a new Merkle tree from leaf data using the default
+    /// `HashSuite::PoseidonKeccak` suite.
     pub fn new(leaf_data: Vec<(&[u8], u8)>) -> Self {
+        Self::new_with_suite(leaf_data, HashSuite::default())
+    }
+
+    /// Create a new Merkle tree from leaf data, hashing leaves and internal
+    /// nodes under the given `HashSuite` instead of the default pairing.
+    pub fn new_with_suite(leaf_data: Vec<(&[u8], u8)>, suite: HashSuite) -> Self {
         let leaves: Vec<[u8; 32]> = leaf_data
             .iter()
-            .map(|(data, tag)| compute_leaf_hash(data, *tag))
+            .map(|(data, tag)| suite.leaf_hash(data, *tag))
             .collect();
 
         let mut tree = vec![leaves.clone()];
         let mut current_level = leaves.clone();
 
-        // Build tree bottom-up
+        // Build tree bottom-up. An odd number of nodes at a level leaves one
+        // unpaired trailing node; rather than duplicating it (which lets two
+        // different leaf multisets collide on the same root — see
+        // `compute_leaf_hash`'s doc comment), it's promoted to the next level
+        // unchanged, so it's never hashed against itself.
         while current_level.len() > 1 {
             let mut next_level = Vec::new();
 
             for chunk in current_level.chunks(2) {
                 let hash = if chunk.len() == 2 {
-                    poseidon_hash(&chunk[0], &chunk[1])
+                    suite.node_hash(&chunk[0], &chunk[1])
                 } else {
-                    // Odd number of nodes, duplicate the last one
-                    poseidon_hash(&chunk[0], &chunk[0])
+                    chunk[0]
                 };
                 next_level.push(hash);
             }
@@ -41,15 +65,794 @@ impl MerkleTree {
             current_level = next_level;
         }
 
-        Self { leaves, tree }
+        Self { leaves, tree }
+    }
+
+    /// Create a new Merkle tree like `new_with_suite`, but hashing leaves
+    /// through a compile-time-fixed [`LeafHasher`](crate::crypto_utils::LeafHasher)
+    /// instead of `suite.leaf_hash`'s bare tag byte — e.g.
+    /// `PersonalizedBlake2bLeafHasher` to bind a field's semantic role into
+    /// its commitment. Internal nodes still hash under `suite.node_hash`,
+    /// so this only changes leaf-level domain separation; callers who
+    /// don't opt in keep today's `new`/`new_with_suite` roots untouched.
+    pub fn new_with_leaf_hasher<L: crate::crypto_utils::LeafHasher>(
+        leaf_data: Vec<(&[u8], u8)>,
+        suite: HashSuite,
+    ) -> Self {
+        let leaves: Vec<[u8; 32]> = leaf_data.iter().map(|(data, tag)| L::hash_leaf(data, *tag)).collect();
+
+        let mut tree = vec![leaves.clone()];
+        let mut current_level = leaves.clone();
+
+        while current_level.len() > 1 {
+            let next_level: Vec<[u8; 32]> = current_level
+                .chunks(2)
+                .map(|chunk| {
+                    if chunk.len() == 2 {
+                        suite.node_hash(&chunk[0], &chunk[1])
+                    } else {
+                        chunk[0]
+                    }
+                })
+                .collect();
+            tree.push(next_level.clone());
+            current_level = next_level;
+        }
+
+        Self { leaves, tree }
+    }
+
+    /// Like `new_with_suite`, but internal node hashing is domain-separated
+    /// from leaf hashing via a node prefix folded into the Poseidon sponge
+    /// (`crate::poseidon::poseidon_node_hash_domain_separated`), closing
+    /// off the leaf/node second-preimage confusion plain
+    /// `poseidon_hash(left, right)` allows — a 32-byte value that's really
+    /// an internal node hash presented as if it were a leaf, or vice
+    /// versa. Only changes suites that route through Poseidon
+    /// (`PoseidonKeccak`/`PoseidonSha256`); `KeccakOnly` is untouched since
+    /// `crate::hashing::keccak256_node_hash` already folds in its own
+    /// `NODE_DOMAIN_TAG`. This produces a different root than
+    /// `new`/`new_with_suite` for the same leaves, so it's gated behind
+    /// this separate constructor (and `verify_proof_domain_separated`)
+    /// rather than changing those in place.
+    pub fn new_domain_separated(leaf_data: Vec<(&[u8], u8)>, suite: HashSuite) -> Self {
+        let leaves: Vec<[u8; 32]> = leaf_data
+            .iter()
+            .map(|(data, tag)| suite.leaf_hash(data, *tag))
+            .collect();
+
+        let mut tree = vec![leaves.clone()];
+        let mut current_level = leaves.clone();
+
+        while current_level.len() > 1 {
+            let next_level: Vec<[u8; 32]> = current_level
+                .chunks(2)
+                .map(|chunk| {
+                    if chunk.len() == 2 {
+                        domain_separated_node_hash(suite, &chunk[0], &chunk[1])
+                    } else {
+                        chunk[0]
+                    }
+                })
+                .collect();
+            tree.push(next_level.clone());
+            current_level = next_level;
+        }
+
+        Self { leaves, tree }
+    }
+
+    /// Verify a proof produced against a `new_domain_separated` tree.
+    pub fn verify_proof_domain_separated(leaf: &[u8; 32], proof: &MerkleProof, root: &[u8; 32], suite: HashSuite) -> bool {
+        let mut current = *leaf;
+
+        for (sibling, direction) in proof.siblings.iter().zip(proof.directions.iter()) {
+            current = if *direction == 0 {
+                domain_separated_node_hash(suite, &current, sibling)
+            } else {
+                domain_separated_node_hash(suite, sibling, &current)
+            };
+        }
+
+        current == *root
+    }
+
+    /// Get the root hash
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.last().unwrap()[0]
+    }
+
+    /// Generate a Merkle proof for a leaf at the given index
+    pub fn generate_proof(&self, leaf_index: usize) -> Result<MerkleProof, String> {
+        if leaf_index >= self.leaves.len() {
+            return Err("Leaf index out of bounds".to_string());
+        }
+
+        let mut siblings = Vec::new();
+        let mut directions = Vec::new();
+        let mut current_index = leaf_index;
+
+        // Traverse from leaf to root. A lone trailing node at a level
+        // contributes no sibling/direction entry at all — it was promoted
+        // unchanged when the tree was built, so the proof simply has one
+        // fewer step there and `current` carries the same value forward.
+        for level in 0..self.tree.len() - 1 {
+            let level_size = self.tree[level].len();
+            let is_unpaired_trailing_node = current_index % 2 == 0 && current_index + 1 >= level_size;
+            if is_unpaired_trailing_node {
+                current_index /= 2;
+                continue;
+            }
+
+            let sibling_index = if current_index % 2 == 0 {
+                current_index + 1
+            } else {
+                current_index - 1
+            };
+
+            siblings.push(self.tree[level][sibling_index]);
+            directions.push(if current_index % 2 == 0 { 0 } else { 1 });
+
+            current_index /= 2;
+        }
+
+        Ok(MerkleProof {
+            siblings,
+            directions,
+        })
+    }
+
+    /// Verify a Merkle proof under the default `HashSuite::PoseidonKeccak`
+    /// suite.
+    pub fn verify_proof(leaf: &[u8; 32], proof: &MerkleProof, root: &[u8; 32]) -> bool {
+        Self::verify_proof_with_suite(leaf, proof, root, HashSuite::default())
+    }
+
+    /// Verify a Merkle proof, recomputing internal node hashes under the
+    /// given `HashSuite` rather than assuming the default pairing.
+    pub fn verify_proof_with_suite(
+        leaf: &[u8; 32],
+        proof: &MerkleProof,
+        root: &[u8; 32],
+        suite: HashSuite,
+    ) -> bool {
+        let mut current = *leaf;
+
+        for (sibling, direction) in proof.siblings.iter().zip(proof.directions.iter()) {
+            current = if *direction == 0 {
+                // Current is left, sibling is right
+                suite.node_hash(&current, sibling)
+            } else {
+                // Current is right, sibling is left
+                suite.node_hash(sibling, &current)
+            };
+        }
+
+        current == *root
+    }
+
+    /// Build a multiproof for several leaves at once, transmitting each
+    /// sibling needed to recompute the root exactly once even when the
+    /// leaves' individual root paths overlap. Walks the tree bottom-up
+    /// starting from `indices`: at each level the "known" node set (leaves
+    /// the caller proved, or parents already reconstructed from a lower
+    /// level) is paired up, and a node's sibling is only pushed onto
+    /// [`MultiProof::siblings`] when that sibling is *not* itself known —
+    /// e.g. two adjacent proven leaves share a parent without either one
+    /// needing to carry the other as a sibling. `verify_multi_merkle_proof`
+    /// mirrors this same walk to know, without being told explicitly, which
+    /// positions it must pull from `siblings` versus which it can compute.
+    pub fn generate_multiproof(&self, indices: &[usize]) -> Result<MultiProof, String> {
+        if indices.is_empty() {
+            return Err("multiproof requires at least one leaf index".to_string());
+        }
+        if let Some(&bad) = indices.iter().find(|&&i| i >= self.leaves.len()) {
+            return Err(format!("leaf index {} out of bounds", bad));
+        }
+
+        let mut leaf_indices: Vec<usize> = indices.to_vec();
+        leaf_indices.sort_unstable();
+        leaf_indices.dedup();
+
+        let mut known: HashSet<usize> = leaf_indices.iter().copied().collect();
+        let mut siblings = Vec::new();
+
+        for level in 0..self.tree.len() - 1 {
+            let level_size = self.tree[level].len();
+            let mut sorted_known: Vec<usize> = known.iter().copied().collect();
+            sorted_known.sort_unstable();
+
+            let mut processed = HashSet::new();
+            let mut next_known = HashSet::new();
+
+            for idx in sorted_known {
+                if processed.contains(&idx) {
+                    continue;
+                }
+
+                if idx % 2 == 0 && idx + 1 >= level_size {
+                    // Lone trailing node: promoted unchanged, no sibling needed.
+                    processed.insert(idx);
+                    next_known.insert(idx / 2);
+                    continue;
+                }
+
+                let sibling_idx = if idx % 2 == 0 { idx + 1 } else { idx - 1 };
+                if !known.contains(&sibling_idx) {
+                    siblings.push(self.tree[level][sibling_idx]);
+                }
+                processed.insert(idx);
+                processed.insert(sibling_idx);
+                next_known.insert(idx / 2);
+            }
+
+            known = next_known;
+        }
+
+        Ok(MultiProof {
+            leaf_indices,
+            siblings,
+            leaf_count: self.leaves.len(),
+        })
+    }
+
+    /// Verify a `MultiProof` under the default `HashSuite`. A thin
+    /// convenience wrapper over
+    /// [`crate::guest_logic::verify_multi_merkle_proof`] (which does the
+    /// actual level-by-level reconstruction) so a caller holding only
+    /// `MerkleTree`/`MultiProof` values doesn't need to reach into
+    /// `guest_logic` directly; use that function instead for a
+    /// non-default `HashSuite`.
+    pub fn verify_multiproof(leaves: &[([u8; 32], usize)], proof: &MultiProof, root: &[u8; 32]) -> bool {
+        let indexed: Vec<(usize, [u8; 32])> = leaves.iter().map(|&(leaf, index)| (index, leaf)).collect();
+        crate::guest_logic::verify_multi_merkle_proof(root, &indexed, proof, HashSuite::default())
+    }
+}
+
+/// A batched Merkle proof for several leaves of the same tree, produced by
+/// [`MerkleTree::generate_multiproof`]. Unlike a `Vec<MerkleProof>`, internal
+/// nodes shared by more than one leaf's path are only stored once in
+/// `siblings`, so proof size grows with the number of *distinct* internal
+/// nodes actually needed rather than `leaves.len() * tree_depth`.
+#[derive(Debug, Clone)]
+pub struct MultiProof {
+    /// Sorted, deduplicated indices of the leaves this proof covers.
+    pub leaf_indices: Vec<usize>,
+    /// Siblings needed to reconstruct the root, in the order a level-by-level
+    /// bottom-up walk over `leaf_indices` first requires them.
+    pub siblings: Vec<[u8; 32]>,
+    /// Total leaf count of the tree the proof was generated from, so a
+    /// verifier without the original tree can still derive each level's size.
+    pub leaf_count: usize,
+}
+
+/// One neighbor leaf bracketing an absent key in a `NonMembershipProof`,
+/// carrying its own sorted-order position and inclusion proof so a
+/// verifier can check it against the tree's root without needing the
+/// tree itself.
+#[derive(Debug, Clone)]
+pub struct BracketingLeaf {
+    pub key: [u8; 32],
+    pub leaf: [u8; 32],
+    pub index: usize,
+    pub proof: MerkleProof,
+}
+
+/// Proof that `key` is absent from a `SortedMerkleTree`: the two leaves
+/// immediately bracketing it in sorted order, or just one at either
+/// boundary (nothing sits below the smallest key, or above the largest).
+/// `leaf_count` lets `SortedMerkleTree::verify_non_membership` check a
+/// one-sided proof's boundary claim without needing the tree itself.
+#[derive(Debug, Clone)]
+pub struct NonMembershipProof {
+    pub leaf_count: usize,
+    pub left: Option<BracketingLeaf>,
+    pub right: Option<BracketingLeaf>,
+}
+
+/// Sorted-leaf variant of `MerkleTree` that additionally supports proving a
+/// key's *absence*: entries are stored in ascending key order, so any two
+/// leaves adjacent in the array are also adjacent in key-space, and
+/// `generate_non_membership_proof` can bracket an absent key between its
+/// two in-order neighbors (or a single neighbor at either boundary) and
+/// prove nothing sits between them. Mirrors the membership/non-membership
+/// split used by ICS-23 / IBC commitment proofs and sparse-Merkle-tree
+/// designs.
+#[derive(Debug, Clone)]
+pub struct SortedMerkleTree {
+    keys: Vec<[u8; 32]>,
+    tree: MerkleTree,
+}
+
+impl SortedMerkleTree {
+    /// Build a tree from `(key, data, tag)` entries, sorting by `key` first
+    /// so adjacency in the tree matches adjacency in key-space. Errs on a
+    /// duplicate key, since bracketing assumes every key appears at most
+    /// once.
+    pub fn new(mut entries: Vec<([u8; 32], &[u8], u8)>, suite: HashSuite) -> Result<Self, String> {
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err("duplicate key in sorted Merkle tree".to_string());
+        }
+
+        let keys: Vec<[u8; 32]> = entries.iter().map(|(key, _, _)| *key).collect();
+        let leaf_data: Vec<(&[u8], u8)> = entries.iter().map(|(_, data, tag)| (*data, *tag)).collect();
+        let tree = MerkleTree::new_with_suite(leaf_data, suite);
+
+        Ok(Self { keys, tree })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.root()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Per-level node counts from the leaf level up to (but not including)
+    /// the root, mirroring `MerkleTree::new_with_suite`'s bottom-up halving
+    /// (`chunks(2)`), so `expected_proof_directions` can replay
+    /// `generate_proof`'s skip/direction decisions without needing the tree
+    /// itself — only `leaf_count`.
+    fn level_sizes(leaf_count: usize) -> Vec<usize> {
+        let mut sizes = vec![leaf_count];
+        let mut current = leaf_count;
+        while current > 1 {
+            current = (current + 1) / 2;
+            sizes.push(current);
+        }
+        sizes
+    }
+
+    /// The `MerkleProof::directions` a genuine proof for leaf `index` (out
+    /// of `leaf_count` total leaves) must have — replaying
+    /// `MerkleTree::generate_proof`'s own skip-the-unpaired-trailing-node
+    /// and left/right bookkeeping level by level, using only `leaf_count`
+    /// (no access to the tree or its sibling hashes). A `BracketingLeaf`'s
+    /// claimed `index` is untrusted prover-supplied metadata — nothing about
+    /// `MerkleTree::verify_proof_with_suite` ties its hash-chain result back
+    /// to that claim — so comparing this against `proof.directions` is what
+    /// actually binds the claimed position to the proof instead of trusting
+    /// it outright.
+    fn expected_proof_directions(mut index: usize, leaf_count: usize) -> Vec<u8> {
+        let sizes = Self::level_sizes(leaf_count);
+        let mut directions = Vec::new();
+
+        for level_size in sizes.iter().take(sizes.len().saturating_sub(1)) {
+            let is_unpaired_trailing_node = index % 2 == 0 && index + 1 >= *level_size;
+            if is_unpaired_trailing_node {
+                index /= 2;
+                continue;
+            }
+
+            directions.push(if index % 2 == 0 { 0 } else { 1 });
+            index /= 2;
+        }
+
+        directions
+    }
+
+    /// Whether `leaf.proof.directions` is actually consistent with
+    /// `leaf.index` under a tree of `leaf_count` leaves — the binding check
+    /// `verify_non_membership` needs so a prover can't pair a genuine
+    /// inclusion proof with a falsely-adjacent claimed `index`.
+    fn claimed_index_matches_proof(leaf: &BracketingLeaf, leaf_count: usize) -> bool {
+        leaf.index < leaf_count
+            && Self::expected_proof_directions(leaf.index, leaf_count) == leaf.proof.directions
+    }
+
+    fn bracketing_leaf(&self, index: usize) -> Result<BracketingLeaf, String> {
+        Ok(BracketingLeaf {
+            key: self.keys[index],
+            leaf: self.tree.leaves[index],
+            index,
+            proof: self.tree.generate_proof(index)?,
+        })
+    }
+
+    /// Prove `key` is absent: brackets it between its two in-order
+    /// neighbors, or just one neighbor at either boundary, each carrying
+    /// its own inclusion proof against `self.root()`. Errs if `key` is
+    /// actually present — non-membership doesn't apply to it.
+    pub fn generate_non_membership_proof(&self, key: &[u8; 32]) -> Result<NonMembershipProof, String> {
+        if self.keys.binary_search(key).is_ok() {
+            return Err("key is present in the tree; non-membership does not apply".to_string());
+        }
+        let insertion_point = self.keys.partition_point(|k| k < key);
+
+        let left = if insertion_point > 0 {
+            Some(self.bracketing_leaf(insertion_point - 1)?)
+        } else {
+            None
+        };
+        let right = if insertion_point < self.keys.len() {
+            Some(self.bracketing_leaf(insertion_point)?)
+        } else {
+            None
+        };
+
+        Ok(NonMembershipProof {
+            leaf_count: self.keys.len(),
+            left,
+            right,
+        })
+    }
+
+    /// Verify a `NonMembershipProof` for `key` against `root` under `suite`:
+    /// every bracketing leaf present must verify against `root`, each leaf's
+    /// claimed `index` must be the one its own `MerkleProof::directions`
+    /// actually encodes (see `claimed_index_matches_proof` — without this, a
+    /// prover could pair two genuine, non-adjacent leaves with favorable
+    /// but fabricated `index` metadata and forge adjacency for a key that
+    /// is, in fact, present), `key` must fall strictly between the two
+    /// neighbors (or strictly beyond the single neighbor present at a
+    /// boundary), and an interior proof's two neighbors must be adjacent
+    /// leaf indices so no third leaf could sit between them.
+    pub fn verify_non_membership(key: &[u8; 32], proof: &NonMembershipProof, root: &[u8; 32], suite: HashSuite) -> bool {
+        match (&proof.left, &proof.right) {
+            (Some(left), Some(right)) => {
+                Self::claimed_index_matches_proof(left, proof.leaf_count)
+                    && Self::claimed_index_matches_proof(right, proof.leaf_count)
+                    && left.index + 1 == right.index
+                    && left.key < *key
+                    && *key < right.key
+                    && MerkleTree::verify_proof_with_suite(&left.leaf, &left.proof, root, suite)
+                    && MerkleTree::verify_proof_with_suite(&right.leaf, &right.proof, root, suite)
+            }
+            (None, Some(right)) => {
+                Self::claimed_index_matches_proof(right, proof.leaf_count)
+                    && right.index == 0
+                    && *key < right.key
+                    && MerkleTree::verify_proof_with_suite(&right.leaf, &right.proof, root, suite)
+            }
+            (Some(left), None) => {
+                Self::claimed_index_matches_proof(left, proof.leaf_count)
+                    && left.index + 1 == proof.leaf_count
+                    && left.key < *key
+                    && MerkleTree::verify_proof_with_suite(&left.leaf, &left.proof, root, suite)
+            }
+            (None, None) => false,
+        }
+    }
+}
+
+/// Proof for `NaryMerkleTree::generate_proof`: one `arity - 1`-sibling
+/// group and the proven child's position within that group, per level, in
+/// leaf-to-root order.
+#[derive(Debug, Clone)]
+pub struct NaryMerkleProof {
+    pub arity: usize,
+    pub siblings: Vec<Vec<[u8; 32]>>,
+    pub positions: Vec<usize>,
+}
+
+/// Node used to pad a short final group at any level, rather than
+/// duplicating real data — the n-ary counterpart of `MerkleTree::new`'s
+/// "promote, don't duplicate" policy for the binary case, which exists to
+/// stop two differently sized leaf sets from colliding on the same root.
+const ARITY_ZERO_NODE: [u8; 32] = [0u8; 32];
+
+/// Generic-arity Merkle tree: each parent absorbs up to `arity` children at
+/// once via `crate::poseidon::poseidon_hash_many` instead of always pairing
+/// two, so a wider tree (4-ary, 8-ary, ...) needs fewer hash invocations and
+/// fewer levels for the same leaf count — fewer constraints for a zk guest
+/// walking an `NaryMerkleProof`. Node hashing is always Poseidon regardless
+/// of `suite` (the whole point is trading Poseidon's wide-absorption
+/// efficiency against tree depth); `suite` only selects the leaf-hashing
+/// domain separation, as elsewhere.
+#[derive(Debug, Clone)]
+pub struct NaryMerkleTree {
+    arity: usize,
+    pub leaves: Vec<[u8; 32]>,
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl NaryMerkleTree {
+    /// Build an `arity`-ary tree from `leaf_data`. `arity` must be a power
+    /// of two of at least 2, and `leaf_data` must be non-empty.
+    pub fn new(leaf_data: Vec<(&[u8], u8)>, arity: usize, suite: HashSuite) -> Result<Self, String> {
+        if arity < 2 || !arity.is_power_of_two() {
+            return Err(format!("arity must be a power of two >= 2, got {}", arity));
+        }
+
+        let leaves: Vec<[u8; 32]> = leaf_data.iter().map(|(data, tag)| suite.leaf_hash(data, *tag)).collect();
+        if leaves.is_empty() {
+            return Err("tree requires at least one leaf".to_string());
+        }
+
+        let mut levels = vec![leaves.clone()];
+        let mut current = leaves.clone();
+
+        while current.len() > 1 {
+            let mut next = Vec::with_capacity((current.len() + arity - 1) / arity);
+            for chunk in current.chunks(arity) {
+                let mut children = chunk.to_vec();
+                children.resize(arity, ARITY_ZERO_NODE);
+                next.push(crate::poseidon::poseidon_hash_many(&children));
+            }
+            levels.push(next.clone());
+            current = next;
+        }
+
+        Ok(Self {
+            arity,
+            leaves,
+            levels,
+        })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    /// Generate a proof for the leaf at `leaf_index`: at each level, the
+    /// `arity - 1` sibling hashes of the group `leaf_index` falls in (zero
+    /// nodes for positions past the group's real children), plus its
+    /// position (`0..arity`) within that group.
+    pub fn generate_proof(&self, leaf_index: usize) -> Result<NaryMerkleProof, String> {
+        if leaf_index >= self.leaves.len() {
+            return Err("leaf index out of bounds".to_string());
+        }
+
+        let mut siblings = Vec::new();
+        let mut positions = Vec::new();
+        let mut current_index = leaf_index;
+
+        for level in 0..self.levels.len() - 1 {
+            let level_size = self.levels[level].len();
+            let group_start = (current_index / self.arity) * self.arity;
+            let position = current_index - group_start;
+
+            let mut group_siblings = Vec::with_capacity(self.arity - 1);
+            for offset in 0..self.arity {
+                if offset == position {
+                    continue;
+                }
+                let sibling_index = group_start + offset;
+                let sibling = if sibling_index < level_size {
+                    self.levels[level][sibling_index]
+                } else {
+                    ARITY_ZERO_NODE
+                };
+                group_siblings.push(sibling);
+            }
+
+            siblings.push(group_siblings);
+            positions.push(position);
+            current_index /= self.arity;
+        }
+
+        Ok(NaryMerkleProof {
+            arity: self.arity,
+            siblings,
+            positions,
+        })
+    }
+
+    /// Verify an `NaryMerkleProof` for `leaf` against `root`: at each
+    /// level, reinsert `current` at its recorded position among that
+    /// level's siblings and rehash the full `arity`-wide node.
+    pub fn verify_proof(leaf: &[u8; 32], proof: &NaryMerkleProof, root: &[u8; 32]) -> bool {
+        if proof.siblings.len() != proof.positions.len() {
+            return false;
+        }
+
+        let mut current = *leaf;
+        for (group_siblings, &position) in proof.siblings.iter().zip(proof.positions.iter()) {
+            if group_siblings.len() != proof.arity - 1 || position >= proof.arity {
+                return false;
+            }
+
+            let mut sibling_iter = group_siblings.iter();
+            let mut children = Vec::with_capacity(proof.arity);
+            for offset in 0..proof.arity {
+                if offset == position {
+                    children.push(current);
+                } else {
+                    children.push(*sibling_iter.next().unwrap());
+                }
+            }
+
+            current = crate::poseidon::poseidon_hash_many(&children);
+        }
+
+        current == *root
+    }
+}
+
+/// Right-sparse, fixed-depth Merkle tree in the style of Lighthouse's
+/// `MerkleTree`: every level is conceptually padded up to its full
+/// power-of-two width (`2^depth` leaf slots) using a precomputed "zero
+/// subtree" hash rather than duplicating the last real leaf. The old
+/// duplicate-last-node handling let a proof for the duplicated leaf and a
+/// proof for its phantom sibling verify identically — an ambiguity that
+/// amounts to a forged inclusion claim. A real leaf can never collide
+/// with a zero subtree root, so that forgery is closed here.
+/// `generate_proof` always returns exactly `depth` siblings, some of which
+/// may be zero nodes; verification reuses `MerkleTree::verify_proof_with_suite`
+/// unchanged, since the proof shape (siblings + direction bits) is the same.
+#[derive(Debug, Clone)]
+pub struct SparseMerkleTree {
+    depth: usize,
+    suite: HashSuite,
+    /// `zero_hashes[0]` is the canonical empty-leaf hash; `zero_hashes[i] =
+    /// suite.node_hash(zero_hashes[i-1], zero_hashes[i-1])` for `i` up to
+    /// `depth`.
+    zero_hashes: Vec<[u8; 32]>,
+    leaves: Vec<[u8; 32]>,
+}
+
+impl SparseMerkleTree {
+    fn zero_hashes(depth: usize, suite: HashSuite) -> Vec<[u8; 32]> {
+        let mut zero_hashes = Vec::with_capacity(depth + 1);
+        zero_hashes.push(suite.leaf_hash(&[], 0));
+        for i in 1..=depth {
+            let prev = zero_hashes[i - 1];
+            zero_hashes.push(suite.node_hash(&prev, &prev));
+        }
+        zero_hashes
+    }
+
+    /// Build a fixed-`depth` sparse tree (capacity `2^depth` leaves) from
+    /// `leaf_data` under the default `HashSuite::PoseidonKeccak`. Errs if
+    /// more leaves are given than the depth's capacity allows.
+    pub fn new(leaf_data: Vec<(&[u8], u8)>, depth: usize) -> Result<Self, String> {
+        Self::new_with_suite(leaf_data, depth, HashSuite::default())
+    }
+
+    /// Build a fixed-`depth` sparse tree, hashing leaves and internal
+    /// nodes under the given `HashSuite`.
+    pub fn new_with_suite(leaf_data: Vec<(&[u8], u8)>, depth: usize, suite: HashSuite) -> Result<Self, String> {
+        let leaves: Vec<[u8; 32]> = leaf_data
+            .iter()
+            .map(|(data, tag)| suite.leaf_hash(data, *tag))
+            .collect();
+
+        let capacity = 1usize << depth;
+        if leaves.len() > capacity {
+            return Err(format!(
+                "{} leaves exceed depth-{} capacity of {}",
+                leaves.len(),
+                depth,
+                capacity
+            ));
+        }
+
+        Ok(Self {
+            depth,
+            suite,
+            zero_hashes: Self::zero_hashes(depth, suite),
+            leaves,
+        })
+    }
+
+    /// The fixed depth this tree (and every proof it produces) commits to,
+    /// so a caller can commit to it alongside the root.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The real leaf hash at `leaf_index`, or the canonical empty-leaf hash
+    /// if that slot was never populated.
+    pub fn leaf_hash_at(&self, leaf_index: usize) -> [u8; 32] {
+        self.leaves.get(leaf_index).copied().unwrap_or(self.zero_hashes[0])
+    }
+
+    /// Value of the subtree rooted at `node_index` within `level` (0 =
+    /// leaf level), where that subtree covers leaf slots
+    /// `[node_index * 2^level, (node_index + 1) * 2^level)`. Short-circuits
+    /// to the precomputed zero subtree the moment the covered range falls
+    /// entirely past the real leaves, rather than recursing all the way
+    /// down an empty subtree.
+    fn subtree_value(&self, level: usize, node_index: usize) -> [u8; 32] {
+        let width = 1usize << level;
+        let leaf_start = node_index * width;
+        if leaf_start >= self.leaves.len() {
+            return self.zero_hashes[level];
+        }
+        if level == 0 {
+            return self.leaves[leaf_start];
+        }
+
+        let left = self.subtree_value(level - 1, node_index * 2);
+        let right = self.subtree_value(level - 1, node_index * 2 + 1);
+        self.suite.node_hash(&left, &right)
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.subtree_value(self.depth, 0)
+    }
+
+    /// Generate a proof for `leaf_index`: always exactly `self.depth`
+    /// siblings (zero nodes past the populated range), verifiable with
+    /// `MerkleTree::verify_proof_with_suite(&self.leaf_hash_at(leaf_index), &proof, &self.root(), suite)`.
+    pub fn generate_proof(&self, leaf_index: usize) -> Result<MerkleProof, String> {
+        if leaf_index >= (1usize << self.depth) {
+            return Err(format!(
+                "leaf index {} out of bounds for depth {}",
+                leaf_index, self.depth
+            ));
+        }
+
+        let mut siblings = Vec::with_capacity(self.depth);
+        let mut directions = Vec::with_capacity(self.depth);
+        let mut node_index = leaf_index;
+
+        for level in 0..self.depth {
+            let sibling_index = node_index ^ 1;
+            siblings.push(self.subtree_value(level, sibling_index));
+            directions.push((node_index % 2) as u8);
+            node_index /= 2;
+        }
+
+        Ok(MerkleProof {
+            siblings,
+            directions,
+        })
+    }
+}
+
+/// Same shape as `MerkleTree`, but hashed under a compile-time-fixed
+/// `Hasher` instead of a runtime `HashSuite`: `H::hash_leaf`/`H::hash_nodes`
+/// are monomorphized at every call site, so there's no suite `match` in the
+/// hot path. Use this when the hash backend is a build-time deployment
+/// choice (e.g. a guest compiled once for `PoseidonHasher`); use
+/// `MerkleTree`/`HashSuite` when the suite is runtime data the verifier
+/// reads out of a committed journal.
+#[derive(Debug, Clone)]
+pub struct GenericMerkleTree<H: Hasher> {
+    pub leaves: Vec<[u8; 32]>,
+    pub tree: Vec<Vec<[u8; 32]>>,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: Hasher> GenericMerkleTree<H> {
+    pub fn new(leaf_data: Vec<(&[u8], u8)>) -> Self {
+        let leaves: Vec<[u8; 32]> = leaf_data.iter().map(|(data, tag)| H::hash_leaf(data, *tag)).collect();
+
+        let mut tree = vec![leaves.clone()];
+        let mut current_level = leaves.clone();
+
+        // See `MerkleTree::new_with_suite`: an unpaired trailing node is
+        // promoted unchanged rather than hashed against itself.
+        while current_level.len() > 1 {
+            let next_level: Vec<[u8; 32]> = current_level
+                .chunks(2)
+                .map(|chunk| {
+                    if chunk.len() == 2 {
+                        H::hash_nodes(&chunk[0], &chunk[1])
+                    } else {
+                        chunk[0]
+                    }
+                })
+                .collect();
+            tree.push(next_level.clone());
+            current_level = next_level;
+        }
+
+        Self {
+            leaves,
+            tree,
+            _hasher: PhantomData,
+        }
     }
 
-    /// Get the root hash
     pub fn root(&self) -> [u8; 32] {
         self.tree.last().unwrap()[0]
     }
 
-    /// Generate a Merkle proof for a leaf at the given index
+    /// Generate a Merkle proof for a leaf at the given index. Identical
+    /// traversal to `MerkleTree::generate_proof` — only the hashing
+    /// underneath differs.
     pub fn generate_proof(&self, leaf_index: usize) -> Result<MerkleProof, String> {
         if leaf_index >= self.leaves.len() {
             return Err("Leaf index out of bounds".to_string());
@@ -59,18 +862,17 @@ impl MerkleTree {
         let mut directions = Vec::new();
         let mut current_index = leaf_index;
 
-        // Traverse from leaf to root
         for level in 0..self.tree.len() - 1 {
             let level_size = self.tree[level].len();
+            let is_unpaired_trailing_node = current_index % 2 == 0 && current_index + 1 >= level_size;
+            if is_unpaired_trailing_node {
+                current_index /= 2;
+                continue;
+            }
+
             let sibling_index = if current_index % 2 == 0 {
-                // Current node is left child
-                if current_index + 1 < level_size {
-                    current_index + 1
-                } else {
-                    current_index // Duplicate for odd number of nodes
-                }
+                current_index + 1
             } else {
-                // Current node is right child
                 current_index - 1
             };
 
@@ -85,28 +887,28 @@ impl MerkleTree {
             directions,
         })
     }
+}
 
-    /// Verify a Merkle proof
-    pub fn verify_proof(leaf: &[u8; 32], proof: &MerkleProof, root: &[u8; 32]) -> bool {
-        let mut current = *leaf;
-
-        for (sibling, direction) in proof.siblings.iter().zip(proof.directions.iter()) {
-            current = if *direction == 0 {
-                // Current is left, sibling is right
-                poseidon_hash(&current, sibling)
-            } else {
-                // Current is right, sibling is left
-                poseidon_hash(sibling, &current)
-            };
-        }
+/// Verify a Merkle proof hashed under the compile-time-fixed `Hasher` `H`,
+/// the `GenericMerkleTree` counterpart to `MerkleTree::verify_proof_with_suite`.
+pub fn verify_merkle_proof_generic<H: Hasher>(leaf: &[u8; 32], proof: &MerkleProof, root: &[u8; 32]) -> bool {
+    let mut current = *leaf;
 
-        current == *root
+    for (sibling, direction) in proof.siblings.iter().zip(proof.directions.iter()) {
+        current = if *direction == 0 {
+            H::hash_nodes(&current, sibling)
+        } else {
+            H::hash_nodes(sibling, &current)
+        };
     }
+
+    current == *root
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crypto_utils::compute_leaf_hash;
 
     #[test]
     fn test_merkle_tree_single_leaf() {
@@ -214,4 +1016,619 @@ mod tests {
         let result = tree.generate_proof(1);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_generic_tree_with_poseidon_hasher_round_trips() {
+        use crate::crypto_utils::PoseidonHasher;
+
+        let data = vec![
+            (b"leaf1".as_slice(), 1u8),
+            (b"leaf2".as_slice(), 2u8),
+            (b"leaf3".as_slice(), 3u8),
+            (b"leaf4".as_slice(), 4u8),
+        ];
+        let tree = GenericMerkleTree::<PoseidonHasher>::new(data);
+        let root = tree.root();
+
+        for i in 0..tree.leaves.len() {
+            let proof = tree.generate_proof(i).unwrap();
+            assert!(verify_merkle_proof_generic::<PoseidonHasher>(
+                &tree.leaves[i],
+                &proof,
+                &root
+            ));
+        }
+    }
+
+    #[test]
+    fn test_generic_tree_with_keccak_hasher_round_trips() {
+        use crate::crypto_utils::Keccak256Hasher;
+
+        let data = vec![(b"leaf1".as_slice(), 1u8), (b"leaf2".as_slice(), 2u8)];
+        let tree = GenericMerkleTree::<Keccak256Hasher>::new(data);
+        let root = tree.root();
+
+        let proof = tree.generate_proof(0).unwrap();
+        assert!(verify_merkle_proof_generic::<Keccak256Hasher>(
+            &tree.leaves[0],
+            &proof,
+            &root
+        ));
+    }
+
+    #[test]
+    fn test_poseidon_and_keccak_generic_trees_disagree_on_the_same_leaves() {
+        use crate::crypto_utils::{Keccak256Hasher, PoseidonHasher};
+
+        let data = vec![(b"leaf1".as_slice(), 1u8), (b"leaf2".as_slice(), 2u8)];
+        let poseidon_tree = GenericMerkleTree::<PoseidonHasher>::new(data.clone());
+        let keccak_tree = GenericMerkleTree::<Keccak256Hasher>::new(data);
+
+        assert_ne!(poseidon_tree.root(), keccak_tree.root());
+    }
+
+    #[test]
+    fn test_multiproof_shares_internal_nodes_across_leaves() {
+        let data: Vec<(&[u8], u8)> = vec![
+            (b"leaf0".as_slice(), 0u8),
+            (b"leaf1".as_slice(), 1u8),
+            (b"leaf2".as_slice(), 2u8),
+            (b"leaf3".as_slice(), 3u8),
+        ];
+        let tree = MerkleTree::new(data);
+
+        // Leaves 0 and 1 share a parent, so that parent's sibling (over
+        // leaves 2,3) should appear exactly once rather than once per leaf.
+        let multiproof = tree.generate_multiproof(&[0, 1]).unwrap();
+        assert_eq!(multiproof.siblings.len(), 1);
+    }
+
+    #[test]
+    fn test_multiproof_five_of_eight_round_trips() {
+        let labels = [
+            "leaf0", "leaf1", "leaf2", "leaf3", "leaf4", "leaf5", "leaf6", "leaf7",
+        ];
+        let data: Vec<(&[u8], u8)> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_bytes(), i as u8))
+            .collect();
+        let tree = MerkleTree::new(data);
+        let root = tree.root();
+
+        let indices = [0usize, 1, 3, 5, 7];
+        let multiproof = tree.generate_multiproof(&indices).unwrap();
+        let leaves: Vec<(usize, [u8; 32])> = indices.iter().map(|&i| (i, tree.leaves[i])).collect();
+
+        assert!(crate::guest_logic::verify_multi_merkle_proof(
+            &root,
+            &leaves,
+            &multiproof,
+            HashSuite::default(),
+        ));
+    }
+
+    #[test]
+    fn test_multiproof_rejects_flipped_leaf() {
+        let labels = [
+            "leaf0", "leaf1", "leaf2", "leaf3", "leaf4", "leaf5", "leaf6", "leaf7",
+        ];
+        let data: Vec<(&[u8], u8)> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_bytes(), i as u8))
+            .collect();
+        let tree = MerkleTree::new(data);
+        let root = tree.root();
+
+        let indices = [0usize, 1, 3, 5, 7];
+        let multiproof = tree.generate_multiproof(&indices).unwrap();
+        let mut leaves: Vec<(usize, [u8; 32])> = indices.iter().map(|&i| (i, tree.leaves[i])).collect();
+        leaves[2].1[0] ^= 0xFF; // flip the leaf at index 3
+
+        assert!(!crate::guest_logic::verify_multi_merkle_proof(
+            &root,
+            &leaves,
+            &multiproof,
+            HashSuite::default(),
+        ));
+    }
+
+    #[test]
+    fn test_multiproof_rejects_missing_sibling() {
+        let labels = [
+            "leaf0", "leaf1", "leaf2", "leaf3", "leaf4", "leaf5", "leaf6", "leaf7",
+        ];
+        let data: Vec<(&[u8], u8)> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_bytes(), i as u8))
+            .collect();
+        let tree = MerkleTree::new(data);
+        let root = tree.root();
+
+        let indices = [0usize, 1, 3, 5, 7];
+        let mut multiproof = tree.generate_multiproof(&indices).unwrap();
+        multiproof.siblings.pop();
+        let leaves: Vec<(usize, [u8; 32])> = indices.iter().map(|&i| (i, tree.leaves[i])).collect();
+
+        assert!(!crate::guest_logic::verify_multi_merkle_proof(
+            &root,
+            &leaves,
+            &multiproof,
+            HashSuite::default(),
+        ));
+    }
+
+    #[test]
+    fn test_merkle_tree_verify_multiproof_wrapper_matches_guest_logic() {
+        let labels = [
+            "leaf0", "leaf1", "leaf2", "leaf3", "leaf4", "leaf5", "leaf6", "leaf7",
+        ];
+        let data: Vec<(&[u8], u8)> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_bytes(), i as u8))
+            .collect();
+        let tree = MerkleTree::new(data);
+        let root = tree.root();
+
+        let indices = [0usize, 1, 3, 5, 7];
+        let multiproof = tree.generate_multiproof(&indices).unwrap();
+        let leaves: Vec<([u8; 32], usize)> = indices.iter().map(|&i| (tree.leaves[i], i)).collect();
+
+        assert!(MerkleTree::verify_multiproof(&leaves, &multiproof, &root));
+    }
+
+    #[test]
+    fn test_nary_tree_rejects_non_power_of_two_arity() {
+        let data: Vec<(&[u8], u8)> = vec![(b"leaf0".as_slice(), 0u8)];
+        assert!(NaryMerkleTree::new(data, 3, HashSuite::default()).is_err());
+    }
+
+    #[test]
+    fn test_nary_tree_four_ary_round_trips_every_proof() {
+        let labels = [
+            "leaf0", "leaf1", "leaf2", "leaf3", "leaf4", "leaf5", "leaf6",
+        ];
+        let data: Vec<(&[u8], u8)> = labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| (label.as_bytes(), i as u8))
+            .collect();
+        let tree = NaryMerkleTree::new(data, 4, HashSuite::default()).unwrap();
+        let root = tree.root();
+
+        for i in 0..tree.leaves.len() {
+            let proof = tree.generate_proof(i).unwrap();
+            assert_eq!(proof.siblings.len(), proof.positions.len());
+            assert!(
+                NaryMerkleTree::verify_proof(&tree.leaves[i], &proof, &root),
+                "leaf {} should verify",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_nary_tree_eight_ary_round_trips_single_level() {
+        let labels = ["a", "b", "c", "d", "e"];
+        let data: Vec<(&[u8], u8)> = labels.iter().map(|s| (s.as_bytes(), 0u8)).collect();
+        let tree = NaryMerkleTree::new(data, 8, HashSuite::default()).unwrap();
+        let root = tree.root();
+
+        let proof = tree.generate_proof(2).unwrap();
+        assert_eq!(proof.siblings.len(), 1);
+        assert_eq!(proof.siblings[0].len(), 7);
+        assert!(NaryMerkleTree::verify_proof(&tree.leaves[2], &proof, &root));
+    }
+
+    #[test]
+    fn test_nary_tree_rejects_tampered_leaf() {
+        let data: Vec<(&[u8], u8)> = vec![
+            (b"leaf0".as_slice(), 0u8),
+            (b"leaf1".as_slice(), 1u8),
+            (b"leaf2".as_slice(), 2u8),
+        ];
+        let tree = NaryMerkleTree::new(data, 4, HashSuite::default()).unwrap();
+        let root = tree.root();
+
+        let proof = tree.generate_proof(0).unwrap();
+        let mut tampered = tree.leaves[0];
+        tampered[0] ^= 0xFF;
+        assert!(!NaryMerkleTree::verify_proof(&tampered, &proof, &root));
+    }
+
+    #[test]
+    fn test_nary_tree_binary_arity_differs_from_default_tree_root() {
+        // Same leaf bytes, but node hashing always goes through
+        // `poseidon_hash_many` here regardless of arity, so even arity 2
+        // doesn't reproduce `MerkleTree`'s root format.
+        let data: Vec<(&[u8], u8)> = vec![(b"leaf0".as_slice(), 0u8), (b"leaf1".as_slice(), 1u8)];
+        let nary_tree = NaryMerkleTree::new(data.clone(), 2, HashSuite::default()).unwrap();
+        let binary_tree = MerkleTree::new(data);
+
+        assert_ne!(nary_tree.root(), binary_tree.root());
+    }
+
+    #[test]
+    fn test_domain_separated_tree_round_trips_proofs() {
+        let data: Vec<(&[u8], u8)> = vec![
+            (b"debtor".as_slice(), 1u8),
+            (b"creditor".as_slice(), 2u8),
+            (b"amount".as_slice(), 3u8),
+        ];
+        let tree = MerkleTree::new_domain_separated(data, HashSuite::default());
+        let root = tree.root();
+
+        for i in 0..tree.leaves.len() {
+            let proof = tree.generate_proof(i).unwrap();
+            assert!(MerkleTree::verify_proof_domain_separated(
+                &tree.leaves[i],
+                &proof,
+                &root,
+                HashSuite::default()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_domain_separated_tree_root_differs_from_plain_tree() {
+        let data: Vec<(&[u8], u8)> = vec![(b"leaf0".as_slice(), 0u8), (b"leaf1".as_slice(), 1u8)];
+        let plain_tree = MerkleTree::new(data.clone());
+        let domain_separated_tree = MerkleTree::new_domain_separated(data, HashSuite::default());
+
+        assert_ne!(plain_tree.root(), domain_separated_tree.root());
+    }
+
+    #[test]
+    fn test_domain_separated_proof_does_not_verify_under_plain_hashing() {
+        let data: Vec<(&[u8], u8)> = vec![
+            (b"leaf0".as_slice(), 0u8),
+            (b"leaf1".as_slice(), 1u8),
+            (b"leaf2".as_slice(), 2u8),
+            (b"leaf3".as_slice(), 3u8),
+        ];
+        let tree = MerkleTree::new_domain_separated(data, HashSuite::default());
+        let root = tree.root();
+
+        let proof = tree.generate_proof(0).unwrap();
+        assert!(!MerkleTree::verify_proof(&tree.leaves[0], &proof, &root));
+    }
+
+    #[test]
+    fn test_sparse_tree_rejects_too_many_leaves_for_depth() {
+        let data: Vec<(&[u8], u8)> =
+            vec![(b"a".as_slice(), 0u8), (b"b".as_slice(), 1u8), (b"c".as_slice(), 2u8)];
+        assert!(SparseMerkleTree::new(data, 1).is_err());
+    }
+
+    #[test]
+    fn test_sparse_tree_proofs_round_trip_for_populated_and_empty_slots() {
+        let data: Vec<(&[u8], u8)> = vec![(b"leaf0".as_slice(), 0u8), (b"leaf1".as_slice(), 1u8)];
+        let tree = SparseMerkleTree::new(data, 3).unwrap();
+        let root = tree.root();
+
+        for leaf_index in 0..(1usize << 3) {
+            let proof = tree.generate_proof(leaf_index).unwrap();
+            assert_eq!(proof.siblings.len(), 3);
+            assert!(
+                MerkleTree::verify_proof_with_suite(
+                    &tree.leaf_hash_at(leaf_index),
+                    &proof,
+                    &root,
+                    HashSuite::default()
+                ),
+                "slot {} should verify, populated or not",
+                leaf_index
+            );
+        }
+    }
+
+    #[test]
+    fn test_sparse_tree_duplicated_leaf_and_phantom_sibling_are_distinguishable() {
+        // Under the old duplicate-last-leaf policy a proof for the
+        // duplicated leaf and for its phantom sibling verified
+        // identically. Here, leaf 0's real proof must not also verify for
+        // the empty slot next to it (leaf 1, which hashes to the zero leaf).
+        let data: Vec<(&[u8], u8)> = vec![(b"leaf0".as_slice(), 0u8)];
+        let tree = SparseMerkleTree::new(data, 2).unwrap();
+        let root = tree.root();
+
+        let proof0 = tree.generate_proof(0).unwrap();
+        assert!(MerkleTree::verify_proof_with_suite(
+            &tree.leaf_hash_at(0),
+            &proof0,
+            &root,
+            HashSuite::default()
+        ));
+
+        // The real leaf's hash differs from the empty slot's zero-leaf
+        // hash, and leaf 0's proof doesn't verify under leaf 1's hash.
+        assert_ne!(tree.leaf_hash_at(0), tree.leaf_hash_at(1));
+        assert!(!MerkleTree::verify_proof_with_suite(
+            &tree.leaf_hash_at(1),
+            &proof0,
+            &root,
+            HashSuite::default()
+        ));
+    }
+
+    #[test]
+    fn test_sparse_tree_root_is_deterministic_for_same_leaves() {
+        let data = || -> Vec<(&[u8], u8)> { vec![(b"leaf0".as_slice(), 0u8), (b"leaf1".as_slice(), 1u8)] };
+        let tree_a = SparseMerkleTree::new(data(), 4).unwrap();
+        let tree_b = SparseMerkleTree::new(data(), 4).unwrap();
+        assert_eq!(tree_a.root(), tree_b.root());
+    }
+
+    #[test]
+    fn test_sparse_tree_empty_matches_zero_hashes_table() {
+        let tree = SparseMerkleTree::new(vec![], 3).unwrap();
+        // An entirely empty depth-3 tree's root is the 3rd zero-subtree
+        // hash, built purely from the empty-leaf hash doubling upward.
+        let suite = HashSuite::default();
+        let z0 = suite.leaf_hash(&[], 0);
+        let z1 = suite.node_hash(&z0, &z0);
+        let z2 = suite.node_hash(&z1, &z1);
+        let z3 = suite.node_hash(&z2, &z2);
+        assert_eq!(tree.root(), z3);
+    }
+
+    fn labeled_leaves(count: usize) -> Vec<(String, u8)> {
+        (0..count).map(|i| (format!("leaf{}", i), i as u8)).collect()
+    }
+
+    #[test]
+    fn test_unbalanced_leaf_counts_every_proof_verifies() {
+        // 1, 3, 7, and 9 (= 2^3 + 1) leaves each exercise a different
+        // unbalanced-tree shape.
+        for &leaf_count in &[1usize, 3, 7, 9] {
+            let labeled = labeled_leaves(leaf_count);
+            let data: Vec<(&[u8], u8)> = labeled.iter().map(|(s, t)| (s.as_bytes(), *t)).collect();
+            let tree = MerkleTree::new(data);
+            let root = tree.root();
+
+            for i in 0..leaf_count {
+                let proof = tree.generate_proof(i).unwrap();
+                assert!(
+                    MerkleTree::verify_proof(&tree.leaves[i], &proof, &root),
+                    "leaf {} of {} should verify",
+                    i,
+                    leaf_count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_unpaired_trailing_node_is_promoted_not_duplicated() {
+        // 3 leaves: level 0 has [l0, l1, l2]; l2 is unpaired and should be
+        // promoted unchanged into level 1 rather than hashed with itself.
+        let data: Vec<(&[u8], u8)> = vec![
+            (b"leaf0".as_slice(), 0u8),
+            (b"leaf1".as_slice(), 1u8),
+            (b"leaf2".as_slice(), 2u8),
+        ];
+        let tree = MerkleTree::new(data);
+
+        assert_eq!(tree.tree[1][1], tree.tree[0][2]);
+    }
+
+    #[test]
+    fn test_tampering_with_promoted_node_path_is_rejected() {
+        let data: Vec<(&[u8], u8)> = vec![
+            (b"leaf0".as_slice(), 0u8),
+            (b"leaf1".as_slice(), 1u8),
+            (b"leaf2".as_slice(), 2u8),
+        ];
+        let tree = MerkleTree::new(data);
+        let root = tree.root();
+
+        // Leaf 2 is the promoted node; its proof should have one fewer step
+        // than a fully-paired leaf, and still reject a tampered leaf.
+        let proof = tree.generate_proof(2).unwrap();
+        assert_eq!(proof.siblings.len(), 1);
+
+        let mut tampered_leaf = tree.leaves[2];
+        tampered_leaf[0] ^= 0xFF;
+        assert!(!MerkleTree::verify_proof(&tampered_leaf, &proof, &root));
+    }
+
+    #[test]
+    fn test_distinct_multisets_cannot_collide_via_duplication() {
+        // Under the old duplicate-last-leaf policy, {A, B, C} could be made
+        // to collide with {A, B, C, C} by constructing the latter so its
+        // last pair hashes to the same promoted value. The promotion policy
+        // removes that avenue entirely: these two multisets produce
+        // different roots.
+        let three: Vec<(&[u8], u8)> = vec![
+            (b"leafA".as_slice(), 0u8),
+            (b"leafB".as_slice(), 1u8),
+            (b"leafC".as_slice(), 2u8),
+        ];
+        let four: Vec<(&[u8], u8)> = vec![
+            (b"leafA".as_slice(), 0u8),
+            (b"leafB".as_slice(), 1u8),
+            (b"leafC".as_slice(), 2u8),
+            (b"leafC".as_slice(), 2u8),
+        ];
+
+        let three_tree = MerkleTree::new(three);
+        let four_tree = MerkleTree::new(four);
+
+        assert_ne!(three_tree.root(), four_tree.root());
+    }
+
+    #[test]
+    fn test_new_with_leaf_hasher_default_matches_new() {
+        use crate::crypto_utils::DefaultLeafHasher;
+
+        let data: Vec<(&[u8], u8)> = vec![
+            (b"debtor".as_slice(), 1u8),
+            (b"creditor".as_slice(), 2u8),
+            (b"amount".as_slice(), 3u8),
+        ];
+        let via_default = MerkleTree::new(data.clone());
+        let via_leaf_hasher = MerkleTree::new_with_leaf_hasher::<DefaultLeafHasher>(data, HashSuite::default());
+
+        assert_eq!(via_default.root(), via_leaf_hasher.root());
+    }
+
+    #[test]
+    fn test_new_with_leaf_hasher_personalized_blake2b_round_trips_proofs() {
+        use crate::crypto_utils::PersonalizedBlake2bLeafHasher;
+
+        let data: Vec<(&[u8], u8)> = vec![
+            (b"debtor".as_slice(), 1u8),
+            (b"creditor".as_slice(), 2u8),
+            (b"amount".as_slice(), 3u8),
+            (b"currency".as_slice(), 4u8),
+            (b"expiry".as_slice(), 5u8),
+        ];
+        let tree = MerkleTree::new_with_leaf_hasher::<PersonalizedBlake2bLeafHasher>(data, HashSuite::default());
+
+        for i in 0..tree.leaves.len() {
+            let proof = tree.generate_proof(i).unwrap();
+            assert!(MerkleTree::verify_proof(&tree.leaves[i], &proof, &tree.root()));
+        }
+    }
+
+    fn sorted_tree_from_keys(keys: &[u8]) -> SortedMerkleTree {
+        let entries: Vec<([u8; 32], &[u8], u8)> = keys
+            .iter()
+            .map(|&k| {
+                let mut key = [0u8; 32];
+                key[31] = k;
+                (key, b"entry".as_slice(), k)
+            })
+            .collect();
+        SortedMerkleTree::new(entries, HashSuite::default()).unwrap()
+    }
+
+    fn key_byte(k: u8) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        key[31] = k;
+        key
+    }
+
+    #[test]
+    fn test_sorted_tree_rejects_duplicate_keys() {
+        let entries: Vec<([u8; 32], &[u8], u8)> =
+            vec![(key_byte(5), b"a".as_slice(), 0), (key_byte(5), b"b".as_slice(), 1)];
+        assert!(SortedMerkleTree::new(entries, HashSuite::default()).is_err());
+    }
+
+    #[test]
+    fn test_non_membership_proof_brackets_interior_gap() {
+        let tree = sorted_tree_from_keys(&[10, 20, 30, 40]);
+        let root = tree.root();
+
+        let proof = tree.generate_non_membership_proof(&key_byte(25)).unwrap();
+        assert!(SortedMerkleTree::verify_non_membership(
+            &key_byte(25),
+            &proof,
+            &root,
+            HashSuite::default()
+        ));
+    }
+
+    #[test]
+    fn test_non_membership_proof_handles_lower_boundary() {
+        let tree = sorted_tree_from_keys(&[10, 20, 30]);
+        let root = tree.root();
+
+        let proof = tree.generate_non_membership_proof(&key_byte(1)).unwrap();
+        assert!(proof.left.is_none());
+        assert!(SortedMerkleTree::verify_non_membership(&key_byte(1), &proof, &root, HashSuite::default()));
+    }
+
+    #[test]
+    fn test_non_membership_proof_handles_upper_boundary() {
+        let tree = sorted_tree_from_keys(&[10, 20, 30]);
+        let root = tree.root();
+
+        let proof = tree.generate_non_membership_proof(&key_byte(99)).unwrap();
+        assert!(proof.right.is_none());
+        assert!(SortedMerkleTree::verify_non_membership(&key_byte(99), &proof, &root, HashSuite::default()));
+    }
+
+    #[test]
+    fn test_non_membership_proof_rejected_for_present_key() {
+        let tree = sorted_tree_from_keys(&[10, 20, 30]);
+        assert!(tree.generate_non_membership_proof(&key_byte(20)).is_err());
+    }
+
+    #[test]
+    fn test_non_membership_proof_rejects_non_adjacent_neighbors() {
+        let tree = sorted_tree_from_keys(&[10, 20, 30, 40]);
+        let root = tree.root();
+
+        // Forge a "gap" by pairing the second and fourth leaves as if they
+        // bracketed a value, skipping the real leaf at index 2.
+        let mut proof = tree.generate_non_membership_proof(&key_byte(25)).unwrap();
+        if let Some(right) = &mut proof.right {
+            let forged = tree.bracketing_leaf(3).unwrap();
+            *right = forged;
+        }
+
+        assert!(!SortedMerkleTree::verify_non_membership(
+            &key_byte(25),
+            &proof,
+            &root,
+            HashSuite::default()
+        ));
+    }
+
+    #[test]
+    fn test_non_membership_proof_rejects_forged_index_metadata() {
+        // Keys [10, 20, 30, 40] at indices [0, 1, 2, 3]. A real proof for
+        // index 3 ("40") is genuinely valid on its own, but claiming it's
+        // index 2 (lying only about the untrusted `index` field, not the
+        // cryptographic proof) would make it look adjacent to the real
+        // index-1 leaf ("20"), forging non-membership for "25" even though
+        // "30" actually sits between them.
+        let tree = sorted_tree_from_keys(&[10, 20, 30, 40]);
+        let root = tree.root();
+
+        let mut proof = tree.generate_non_membership_proof(&key_byte(25)).unwrap();
+        if let Some(right) = &mut proof.right {
+            let mut forged = tree.bracketing_leaf(3).unwrap();
+            forged.index = 2;
+            *right = forged;
+        }
+
+        assert!(!SortedMerkleTree::verify_non_membership(
+            &key_byte(25),
+            &proof,
+            &root,
+            HashSuite::default()
+        ));
+    }
+
+    #[test]
+    fn test_non_membership_proof_rejects_wrong_root() {
+        let tree = sorted_tree_from_keys(&[10, 20, 30, 40]);
+        let proof = tree.generate_non_membership_proof(&key_byte(25)).unwrap();
+
+        let wrong_root = [0xABu8; 32];
+        assert!(!SortedMerkleTree::verify_non_membership(
+            &key_byte(25),
+            &proof,
+            &wrong_root,
+            HashSuite::default()
+        ));
+    }
+
+    #[test]
+    fn test_new_with_leaf_hasher_changes_root_vs_default_hasher() {
+        use crate::crypto_utils::PersonalizedBlake2bLeafHasher;
+
+        let data: Vec<(&[u8], u8)> = vec![(b"amount".as_slice(), 3u8), (b"currency".as_slice(), 4u8)];
+        let default_tree = MerkleTree::new(data.clone());
+        let personalized_tree =
+            MerkleTree::new_with_leaf_hasher::<PersonalizedBlake2bLeafHasher>(data, HashSuite::default());
+
+        assert_ne!(default_tree.root(), personalized_tree.root());
+    }
 }
@@ -0,0 +1,412 @@
+use crate::crypto_utils::HashSuite;
+use crate::merkle_tree::MerkleProof;
+
+/// One node in the mountain range: its hash and height (0 = leaf).
+#[derive(Debug, Clone, Copy)]
+struct Peak {
+    hash: [u8; 32],
+    height: u32,
+}
+
+/// Append-only accumulator for payment instructions that arrive one at a
+/// time rather than as a fixed batch. Unlike `MerkleTree`, which rebuilds
+/// its whole structure from a `Vec` on every call, [`Self::append`] only
+/// merges the handful of peaks that share the new leaf's height — O(log n)
+/// amortized per append instead of O(n) for a full rebuild.
+///
+/// Internally this is a list of "peaks", each a subtree root plus its
+/// height. Appending pushes a new height-0 peak, then repeatedly merges the
+/// two rightmost peaks while they share a height, so the peak heights
+/// (left to right) always strictly decrease — the binary-counter invariant
+/// every Merkle Mountain Range relies on. The accumulator's root is the
+/// "bagged" peaks: fold from the rightmost peak leftward, combining each
+/// peak with the running accumulator via [`HashSuite::node_hash`].
+#[derive(Debug, Clone)]
+pub struct MerkleMountainRange {
+    suite: HashSuite,
+    peaks: Vec<Peak>,
+    /// Leaf indices currently rooted under each entry of `peaks`, in the
+    /// same order. Needed so a merge can extend every already-appended
+    /// leaf's membership proof by one more step instead of only the
+    /// newest leaf's.
+    peak_leaves: Vec<Vec<usize>>,
+    /// Per-leaf sibling path accumulated so far, from the leaf up to
+    /// whichever peak currently contains it. Frozen in place once that
+    /// peak stops merging further; [`Self::proof_for`] extends a copy of
+    /// it with the peak-bagging siblings needed to reach the current root.
+    leaf_paths: Vec<MerkleProof>,
+}
+
+impl MerkleMountainRange {
+    /// Create an empty accumulator under the default `HashSuite::PoseidonKeccak`.
+    pub fn new() -> Self {
+        Self::with_suite(HashSuite::default())
+    }
+
+    /// Create an empty accumulator, hashing leaves and internal nodes under
+    /// `suite` instead of the default pairing.
+    pub fn with_suite(suite: HashSuite) -> Self {
+        Self {
+            suite,
+            peaks: Vec::new(),
+            peak_leaves: Vec::new(),
+            leaf_paths: Vec::new(),
+        }
+    }
+
+    /// Number of leaves appended so far.
+    pub fn len(&self) -> usize {
+        self.leaf_paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaf_paths.is_empty()
+    }
+
+    /// Append a tagged leaf, merging peaks of equal height just like
+    /// incrementing a binary counter. Returns the new leaf's index, which
+    /// [`Self::proof_for`] takes to produce a membership proof.
+    pub fn append(&mut self, data: &[u8], tag: u8) -> usize {
+        let leaf_index = self.leaf_paths.len();
+
+        self.peaks.push(Peak {
+            hash: self.suite.leaf_hash(data, tag),
+            height: 0,
+        });
+        self.peak_leaves.push(vec![leaf_index]);
+        self.leaf_paths.push(MerkleProof {
+            siblings: Vec::new(),
+            directions: Vec::new(),
+        });
+
+        self.merge_equal_height_peaks();
+        leaf_index
+    }
+
+    /// Append an already-computed root as a new leaf, bypassing
+    /// `HashSuite::leaf_hash`. For batching many payment instructions under
+    /// one accumulator, each instruction's own `PaymentInstructionInput::root`
+    /// is itself already a commitment over that instruction's fields — it
+    /// isn't raw data that still needs leaf-tagging the way `append`'s
+    /// `data`/`tag` pair does.
+    pub fn append_root(&mut self, root: [u8; 32]) -> usize {
+        let leaf_index = self.leaf_paths.len();
+
+        self.peaks.push(Peak { hash: root, height: 0 });
+        self.peak_leaves.push(vec![leaf_index]);
+        self.leaf_paths.push(MerkleProof {
+            siblings: Vec::new(),
+            directions: Vec::new(),
+        });
+
+        self.merge_equal_height_peaks();
+        leaf_index
+    }
+
+    /// Repeatedly merge the two rightmost peaks while they share a height —
+    /// shared by `append` and `append_root` since the merge step doesn't
+    /// care how the newest leaf's hash was produced.
+    fn merge_equal_height_peaks(&mut self) {
+        while self.peaks.len() >= 2 {
+            let right = self.peaks[self.peaks.len() - 1];
+            let left = self.peaks[self.peaks.len() - 2];
+            if left.height != right.height {
+                break;
+            }
+
+            self.peaks.pop();
+            self.peaks.pop();
+            let right_leaves = self.peak_leaves.pop().unwrap();
+            let mut left_leaves = self.peak_leaves.pop().unwrap();
+
+            for &idx in &left_leaves {
+                self.leaf_paths[idx].siblings.push(right.hash);
+                self.leaf_paths[idx].directions.push(0); // leaf's side is left
+            }
+            for &idx in &right_leaves {
+                self.leaf_paths[idx].siblings.push(left.hash);
+                self.leaf_paths[idx].directions.push(1); // leaf's side is right
+            }
+
+            self.peaks.push(Peak {
+                hash: self.suite.node_hash(&left.hash, &right.hash),
+                height: left.height + 1,
+            });
+            left_leaves.extend(right_leaves);
+            self.peak_leaves.push(left_leaves);
+        }
+    }
+
+    /// The current root: peaks bagged right to left, `acc = node_hash(peak,
+    /// acc)` seeded with the rightmost peak's own hash. `None` until the
+    /// first leaf is appended.
+    pub fn root(&self) -> Option<[u8; 32]> {
+        self.bag_from(0)
+    }
+
+    /// Bag `self.peaks[start..]` into a single hash, or `None` if `start`
+    /// is past the end. Used both for [`Self::root`] (`start == 0`) and to
+    /// fold in the peaks to the right of a leaf's own peak when building
+    /// its membership proof.
+    fn bag_from(&self, start: usize) -> Option<[u8; 32]> {
+        let mut iter = self.peaks[start..].iter().rev();
+        let mut acc = iter.next()?.hash;
+        for peak in iter {
+            acc = self.suite.node_hash(&peak.hash, &acc);
+        }
+        Some(acc)
+    }
+
+    /// Build a membership proof for `leaf_index`, verifiable against
+    /// [`Self::root`] via `MerkleTree::verify_proof_with_suite`. Combines the
+    /// leaf's frozen path up to its containing peak with however many other
+    /// peaks currently exist to re-bag the root, so a proof taken right
+    /// after `append` and one taken many appends later both verify, even
+    /// though the root (and the bagging suffix) changed in between.
+    pub fn proof_for(&self, leaf_index: usize) -> Result<MerkleProof, String> {
+        if leaf_index >= self.leaf_paths.len() {
+            return Err(format!("leaf index {} out of bounds", leaf_index));
+        }
+        let peak_position = self
+            .peak_leaves
+            .iter()
+            .position(|leaves| leaves.contains(&leaf_index))
+            .ok_or_else(|| format!("leaf {} not found under any peak", leaf_index))?;
+
+        let mut siblings = self.leaf_paths[leaf_index].siblings.clone();
+        let mut directions = self.leaf_paths[leaf_index].directions.clone();
+
+        // Fold in everything to the right of our peak as one sibling: the
+        // bagged value of peaks[peak_position + 1..] plays the role of the
+        // running accumulator in the bagging fold, with our peak on the left.
+        if let Some(right_bag) = self.bag_from(peak_position + 1) {
+            siblings.push(right_bag);
+            directions.push(0);
+        }
+        // Then fold in every peak to our left, in order, each time becoming
+        // the new running accumulator's left sibling.
+        for j in (0..peak_position).rev() {
+            siblings.push(self.peaks[j].hash);
+            directions.push(1);
+        }
+
+        Ok(MerkleProof {
+            siblings,
+            directions,
+        })
+    }
+
+    /// Split view of the same membership proof `Self::proof_for` flattens
+    /// into one `MerkleProof`: the leaf's within-peak authentication path,
+    /// the position of its containing peak among `self.peaks`, and every
+    /// other current peak's hash. `Self::verify_inclusion` recombines the
+    /// three — first rehashing `path_to_peak` to recover the containing
+    /// peak's hash, then bagging it against `other_peaks` — to reproduce
+    /// the root without ever merging the two stages into a single proof.
+    pub fn prove_inclusion(
+        &self,
+        leaf_index: usize,
+    ) -> Result<(MerkleProof, usize, Vec<[u8; 32]>), String> {
+        if leaf_index >= self.leaf_paths.len() {
+            return Err(format!("leaf index {} out of bounds", leaf_index));
+        }
+        let peak_position = self
+            .peak_leaves
+            .iter()
+            .position(|leaves| leaves.contains(&leaf_index))
+            .ok_or_else(|| format!("leaf {} not found under any peak", leaf_index))?;
+
+        let path_to_peak = self.leaf_paths[leaf_index].clone();
+        let other_peaks: Vec<[u8; 32]> = self
+            .peaks
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != peak_position)
+            .map(|(_, peak)| peak.hash)
+            .collect();
+
+        Ok((path_to_peak, peak_position, other_peaks))
+    }
+
+    /// Verify a `prove_inclusion` proof against `root`: rehash `path_to_peak`
+    /// from `leaf` to recover its containing peak's hash, reinsert that hash
+    /// at `peak_position` among `other_peaks`, then bag right-to-left the
+    /// same way `Self::root` does.
+    pub fn verify_inclusion(
+        leaf: &[u8; 32],
+        path_to_peak: &MerkleProof,
+        peak_position: usize,
+        other_peaks: &[[u8; 32]],
+        root: &[u8; 32],
+        suite: HashSuite,
+    ) -> bool {
+        if peak_position > other_peaks.len() {
+            return false;
+        }
+
+        let mut peak_hash = *leaf;
+        for (sibling, direction) in path_to_peak.siblings.iter().zip(path_to_peak.directions.iter()) {
+            peak_hash = if *direction == 0 {
+                suite.node_hash(&peak_hash, sibling)
+            } else {
+                suite.node_hash(sibling, &peak_hash)
+            };
+        }
+
+        let mut peaks = other_peaks.to_vec();
+        peaks.insert(peak_position, peak_hash);
+
+        let mut iter = peaks.iter().rev();
+        let mut acc = match iter.next() {
+            Some(&hash) => hash,
+            None => return false,
+        };
+        for peak in iter {
+            acc = suite.node_hash(peak, &acc);
+        }
+
+        acc == *root
+    }
+}
+
+impl Default for MerkleMountainRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merkle_tree::MerkleTree;
+
+    fn verifies(mmr: &MerkleMountainRange, leaf_index: usize, leaf: [u8; 32]) -> bool {
+        let proof = mmr.proof_for(leaf_index).unwrap();
+        let root = mmr.root().unwrap();
+        MerkleTree::verify_proof_with_suite(&leaf, &proof, &root, HashSuite::default())
+    }
+
+    #[test]
+    fn test_single_leaf_root_is_its_own_hash() {
+        let mut mmr = MerkleMountainRange::new();
+        mmr.append(b"leaf0", 1u8);
+        let leaf = HashSuite::default().leaf_hash(b"leaf0", 1u8);
+        assert_eq!(mmr.root(), Some(leaf));
+        assert!(verifies(&mmr, 0, leaf));
+    }
+
+    #[test]
+    fn test_incremental_appends_keep_every_historical_proof_valid() {
+        let mut mmr = MerkleMountainRange::new();
+        let mut leaves = Vec::new();
+
+        for i in 0..9u32 {
+            let data = format!("payment-instruction-{}", i);
+            let tag = (i % 5) as u8 + 1;
+            let index = mmr.append(data.as_bytes(), tag);
+            leaves.push((index, HashSuite::default().leaf_hash(data.as_bytes(), tag)));
+
+            // Every leaf appended so far must still verify against the
+            // *current* root, not just the one at the time it was added.
+            for &(leaf_index, leaf_hash) in &leaves {
+                assert!(
+                    verifies(&mmr, leaf_index, leaf_hash),
+                    "leaf {} failed to verify after {} total appends",
+                    leaf_index,
+                    i + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampered_proof_is_rejected() {
+        let mut mmr = MerkleMountainRange::new();
+        for i in 0..5u32 {
+            mmr.append(format!("leaf-{}", i).as_bytes(), 1u8);
+        }
+
+        let leaf = HashSuite::default().leaf_hash(b"leaf-2", 1u8);
+        let mut proof = mmr.proof_for(2).unwrap();
+        proof.directions[0] = 1 - proof.directions[0];
+
+        let root = mmr.root().unwrap();
+        assert!(!MerkleTree::verify_proof_with_suite(
+            &leaf,
+            &proof,
+            &root,
+            HashSuite::default()
+        ));
+    }
+
+    #[test]
+    fn test_len_and_is_empty() {
+        let mut mmr = MerkleMountainRange::new();
+        assert!(mmr.is_empty());
+        mmr.append(b"leaf0", 1u8);
+        assert_eq!(mmr.len(), 1);
+        assert!(!mmr.is_empty());
+    }
+
+    #[test]
+    fn test_append_root_batches_precomputed_instruction_roots() {
+        let mut mmr = MerkleMountainRange::new();
+        let instruction_roots: Vec<[u8; 32]> = (0u8..7).map(|i| [i; 32]).collect();
+
+        for &root in &instruction_roots {
+            mmr.append_root(root);
+        }
+
+        assert_eq!(mmr.len(), instruction_roots.len());
+        for (i, &root) in instruction_roots.iter().enumerate() {
+            assert!(verifies(&mmr, i, root));
+        }
+    }
+
+    #[test]
+    fn test_prove_inclusion_round_trips_for_every_leaf() {
+        let mut mmr = MerkleMountainRange::new();
+        let mut leaves = Vec::new();
+        for i in 0..9u32 {
+            let data = format!("payment-instruction-{}", i);
+            let index = mmr.append(data.as_bytes(), 1u8);
+            leaves.push((index, HashSuite::default().leaf_hash(data.as_bytes(), 1u8)));
+        }
+        let root = mmr.root().unwrap();
+
+        for &(leaf_index, leaf_hash) in &leaves {
+            let (path_to_peak, peak_position, other_peaks) = mmr.prove_inclusion(leaf_index).unwrap();
+            assert!(MerkleMountainRange::verify_inclusion(
+                &leaf_hash,
+                &path_to_peak,
+                peak_position,
+                &other_peaks,
+                &root,
+                HashSuite::default(),
+            ));
+        }
+    }
+
+    #[test]
+    fn test_verify_inclusion_rejects_tampered_path() {
+        let mut mmr = MerkleMountainRange::new();
+        for i in 0..5u32 {
+            mmr.append(format!("leaf-{}", i).as_bytes(), 1u8);
+        }
+        let root = mmr.root().unwrap();
+        let leaf = HashSuite::default().leaf_hash(b"leaf-2", 1u8);
+
+        let (mut path_to_peak, peak_position, other_peaks) = mmr.prove_inclusion(2).unwrap();
+        assert!(!path_to_peak.directions.is_empty(), "leaf 2 merges twice by the 5th append");
+        path_to_peak.directions[0] = 1 - path_to_peak.directions[0];
+
+        assert!(!MerkleMountainRange::verify_inclusion(
+            &leaf,
+            &path_to_peak,
+            peak_position,
+            &other_peaks,
+            &root,
+            HashSuite::default(),
+        ));
+    }
+}
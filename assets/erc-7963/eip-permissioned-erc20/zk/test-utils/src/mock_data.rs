@@ -1,4 +1,4 @@
-use crate::crypto_utils::{canonicalize_json, keccak256};
+use crate::crypto_utils::{canonicalize_json, keccak256, HashSuite};
 use crate::payment_instruction_generator::{PaymentInstructionInput, PaymentInstructionOutput};
 use rand::Rng;
 
@@ -41,6 +41,8 @@ impl MockData {
             currency_proof_directions: vec![],
             expiry_proof_siblings: vec![],
             expiry_proof_directions: vec![],
+            hash_suite: HashSuite::default(),
+            encrypted_payload: None,
         }
     }
 
@@ -55,6 +57,7 @@ impl MockData {
             max_amount_milli: input.max_amount_milli,
             currency_hash: input.currency_hash,
             expiry: input.expiry,
+            hash_suite: input.hash_suite,
         }
     }
 
@@ -166,6 +169,28 @@ impl MockData {
         input
     }
 
+    /// Create input whose debtor account data carries an IBAN that fails the
+    /// ISO 13616 mod-97 checksum, for `ProofValidator::validate_iban` tests
+    pub fn invalid_iban_input() -> PaymentInstructionInput {
+        let mut input = Self::simple_valid_input();
+        input.debtor_data = r#"{"Nm": "Alice Corp", "PstlAdr": {"Ctry": "US"}, "DbtrAcct": {"Id": {"IBAN": "GB00WEST12345698765432"}}}"#.to_string();
+        input
+    }
+
+    /// Ordered event stream that deposits a payment, disputes it, then
+    /// resolves the dispute in the debtor's favor.
+    pub fn dispute_then_resolve_events() -> Vec<crate::payment_state::PaymentEvent> {
+        use crate::payment_state::PaymentEvent::*;
+        vec![Deposit, Dispute, Resolve]
+    }
+
+    /// Ordered event stream that deposits a payment, disputes it, then
+    /// reverses it via chargeback.
+    pub fn dispute_then_chargeback_events() -> Vec<crate::payment_state::PaymentEvent> {
+        use crate::payment_state::PaymentEvent::*;
+        vec![Deposit, Dispute, Chargeback]
+    }
+
     /// Create a batch of random inputs for stress testing
     pub fn random_batch(count: usize) -> Vec<PaymentInstructionInput> {
         let mut rng = rand::thread_rng();
@@ -1,9 +1,25 @@
-use crate::crypto_utils::{canonicalize_json, keccak256};
+use crate::crypto_utils::{canonicalize_json, keccak256, HashSuite, LeafHasher};
+use crate::incremental_witness::IncrementalWitness;
 use crate::merkle_tree::MerkleTree;
+use crate::note_encryption::{decrypt_note_payload, encrypt_note_payload, EncryptedNotePayload};
+use crate::pain001_xml::{parse_iso20022, parse_pain001, ParseError};
+use arbitrary::Arbitrary;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+/// Which of the five Merkle leaves a `PaymentInstructionGenerator::refresh_field`
+/// call targets. Each discriminant is the field's leaf position; the leaf is
+/// hashed under tag position + 1, as in `regenerate_merkle_proofs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentField {
+    Debtor = 0,
+    Creditor = 1,
+    Amount = 2,
+    Currency = 3,
+    Expiry = 4,
+}
+
+#[derive(Arbitrary, Clone, Debug, Deserialize, Serialize)]
 pub struct PaymentInstructionInput {
     // Public inputs (will be committed)
     pub root: [u8; 32],
@@ -32,9 +48,57 @@ pub struct PaymentInstructionInput {
     pub currency_proof_directions: Vec<u8>,
     pub expiry_proof_siblings: Vec<[u8; 32]>,
     pub expiry_proof_directions: Vec<u8>,
+
+    /// Which `HashSuite` the public hashes/root above were committed under.
+    /// Committed into the journal so a verifier recomputes with the right
+    /// primitive instead of assuming a fixed pairing.
+    pub hash_suite: HashSuite,
+
+    /// `debtor_data`/`creditor_data` encrypted for an intended recipient via
+    /// [`encrypt_private_data`](PaymentInstructionInput::encrypt_private_data),
+    /// absent until that's called. The public hashes above stay computed
+    /// over the plaintext, so this field never affects proof generation or
+    /// verification — it only gates who can read the cleartext back out.
+    #[serde(default)]
+    pub encrypted_payload: Option<EncryptedNotePayload>,
+}
+
+impl PaymentInstructionInput {
+    /// Encrypt `debtor_data`/`creditor_data` (canonicalized, matching what's
+    /// hashed into `debtor_hash`/`creditor_hash`) for `recipient_pk`,
+    /// populating `encrypted_payload`. Each field gets its own ephemeral
+    /// key, so learning one field's ephemeral pubkey doesn't help decrypt
+    /// the other.
+    pub fn encrypt_private_data(&mut self, recipient_pk: &x25519_dalek::PublicKey) {
+        let debtor_plaintext = canonicalize_json(&self.debtor_data);
+        let creditor_plaintext = canonicalize_json(&self.creditor_data);
+
+        self.encrypted_payload = Some(EncryptedNotePayload {
+            debtor: encrypt_note_payload(debtor_plaintext.as_bytes(), recipient_pk),
+            creditor: encrypt_note_payload(creditor_plaintext.as_bytes(), recipient_pk),
+        });
+    }
+
+    /// Recover `(debtor_data, creditor_data)` from `encrypted_payload` using
+    /// `recipient_sk`. Errors if no payload has been encrypted yet, the key
+    /// doesn't match, or the ciphertext was tampered with.
+    pub fn decrypt_private_data(&self, recipient_sk: &x25519_dalek::StaticSecret) -> Result<(String, String), String> {
+        let payload = self
+            .encrypted_payload
+            .as_ref()
+            .ok_or_else(|| "no encrypted payload present on this input".to_string())?;
+
+        let debtor_bytes = decrypt_note_payload(&payload.debtor, recipient_sk)?;
+        let creditor_bytes = decrypt_note_payload(&payload.creditor, recipient_sk)?;
+
+        let debtor_data = String::from_utf8(debtor_bytes).map_err(|e| e.to_string())?;
+        let creditor_data = String::from_utf8(creditor_bytes).map_err(|e| e.to_string())?;
+
+        Ok((debtor_data, creditor_data))
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct PaymentInstructionOutput {
     pub root: [u8; 32],
     pub debtor_hash: [u8; 32],
@@ -43,17 +107,115 @@ pub struct PaymentInstructionOutput {
     pub max_amount_milli: u64,
     pub currency_hash: [u8; 32],
     pub expiry: u64,
+    pub hash_suite: HashSuite,
+}
+
+/// Length in bytes of the canonical payload packed by `encode`/`decode`:
+/// four 32-byte hashes, three 8-byte big-endian integers, plus a 1-byte
+/// `HashSuite` identifier.
+const ENCODED_PAYLOAD_LEN: usize = 32 * 4 + 8 * 3 + 1;
+
+impl PaymentInstructionOutput {
+    /// Serialize the public fields into a compact, checksummed,
+    /// human-transmittable bech32 string (human-readable prefix `pi`),
+    /// suitable for copy/paste or QR encoding.
+    pub fn encode(&self) -> String {
+        let mut payload = Vec::with_capacity(ENCODED_PAYLOAD_LEN);
+        payload.extend_from_slice(&self.root);
+        payload.extend_from_slice(&self.debtor_hash);
+        payload.extend_from_slice(&self.creditor_hash);
+        payload.extend_from_slice(&self.min_amount_milli.to_be_bytes());
+        payload.extend_from_slice(&self.max_amount_milli.to_be_bytes());
+        payload.extend_from_slice(&self.currency_hash);
+        payload.extend_from_slice(&self.expiry.to_be_bytes());
+        payload.push(self.hash_suite.id());
+
+        let five_bit = crate::bech32::convert_bits(&payload, 8, 5, true)
+            .expect("8-to-5 bit conversion of a fixed-size payload cannot fail");
+        crate::bech32::encode("pi", &five_bit)
+    }
+
+    /// Parse a string produced by `encode`, verifying the bech32 checksum
+    /// and rejecting any single-character corruption.
+    pub fn decode(encoded: &str) -> Result<Self, String> {
+        let (hrp, five_bit) = crate::bech32::decode(encoded)?;
+        if hrp != "pi" {
+            return Err(format!("unexpected human-readable prefix: {}", hrp));
+        }
+
+        let payload = crate::bech32::convert_bits(&five_bit, 5, 8, false)?;
+        if payload.len() != ENCODED_PAYLOAD_LEN {
+            return Err(format!(
+                "unexpected decoded payload length: expected {}, got {}",
+                ENCODED_PAYLOAD_LEN,
+                payload.len()
+            ));
+        }
+
+        let mut root = [0u8; 32];
+        root.copy_from_slice(&payload[0..32]);
+        let mut debtor_hash = [0u8; 32];
+        debtor_hash.copy_from_slice(&payload[32..64]);
+        let mut creditor_hash = [0u8; 32];
+        creditor_hash.copy_from_slice(&payload[64..96]);
+        let min_amount_milli = u64::from_be_bytes(payload[96..104].try_into().unwrap());
+        let max_amount_milli = u64::from_be_bytes(payload[104..112].try_into().unwrap());
+        let mut currency_hash = [0u8; 32];
+        currency_hash.copy_from_slice(&payload[112..144]);
+        let expiry = u64::from_be_bytes(payload[144..152].try_into().unwrap());
+        let hash_suite = HashSuite::from_id(payload[152])?;
+
+        Ok(PaymentInstructionOutput {
+            root,
+            debtor_hash,
+            creditor_hash,
+            min_amount_milli,
+            max_amount_milli,
+            currency_hash,
+            expiry,
+            hash_suite,
+        })
+    }
 }
 
 pub struct PaymentInstructionGenerator {
     rng: rand::rngs::ThreadRng,
+    hash_suite: HashSuite,
 }
 
 impl PaymentInstructionGenerator {
     pub fn new() -> Self {
         Self {
             rng: rand::thread_rng(),
+            hash_suite: HashSuite::default(),
+        }
+    }
+
+    /// Build a generator that commits every input it produces under `suite`
+    /// instead of the default `HashSuite::PoseidonKeccak`.
+    pub fn with_hash_suite(suite: HashSuite) -> Self {
+        Self {
+            rng: rand::thread_rng(),
+            hash_suite: suite,
+        }
+    }
+
+    /// Write `input` to `path` as a JSON conformance fixture, recording
+    /// `expect` as the outcome a conforming implementation must reproduce
+    /// when it replays this input through proof generation. See
+    /// `crate::fixtures::run_fixture_suite` for replaying a directory of
+    /// these back through `generate_and_verify_proof`.
+    pub fn dump_fixture(
+        &self,
+        input: &PaymentInstructionInput,
+        expect: crate::fixtures::FixtureExpectation,
+        path: &std::path::Path,
+    ) -> std::io::Result<()> {
+        crate::fixtures::PaymentInstructionFixture {
+            input: input.clone(),
+            expect,
         }
+        .dump_fixture(path)
     }
 
     /// Generate a valid payment instruction input with proper Merkle proofs
@@ -75,9 +237,9 @@ impl PaymentInstructionGenerator {
         let expiry = execution_date.parse::<u64>().unwrap();
 
         // Compute hashes
-        let debtor_hash = keccak256(canonicalize_json(&debtor_data).as_bytes());
-        let creditor_hash = keccak256(canonicalize_json(&creditor_data).as_bytes());
-        let currency_hash = keccak256(currency.as_bytes());
+        let debtor_hash = self.hash_suite.field_hash(canonicalize_json(&debtor_data).as_bytes());
+        let creditor_hash = self.hash_suite.field_hash(canonicalize_json(&creditor_data).as_bytes());
+        let currency_hash = self.hash_suite.field_hash(currency.as_bytes());
 
         // Create Merkle tree with all fields
         let amount_bytes = amount_value.to_be_bytes();
@@ -91,7 +253,7 @@ impl PaymentInstructionGenerator {
             (expiry_bytes.as_slice(), 5u8),
         ];
 
-        let tree = MerkleTree::new(tree_data);
+        let tree = MerkleTree::new_with_suite(tree_data, self.hash_suite);
         let root = tree.root();
 
         // Generate proofs for each field
@@ -124,6 +286,8 @@ impl PaymentInstructionGenerator {
             currency_proof_directions: currency_proof.directions,
             expiry_proof_siblings: expiry_proof.siblings,
             expiry_proof_directions: expiry_proof.directions,
+            hash_suite: self.hash_suite,
+            encrypted_payload: None,
         }
     }
 
@@ -241,10 +405,12 @@ impl PaymentInstructionGenerator {
     }
 
     pub fn regenerate_merkle_proofs(&mut self, input: &mut PaymentInstructionInput) {
-        // Recompute hashes
-        input.debtor_hash = keccak256(canonicalize_json(&input.debtor_data).as_bytes());
-        input.creditor_hash = keccak256(canonicalize_json(&input.creditor_data).as_bytes());
-        input.currency_hash = keccak256(input.currency.as_bytes());
+        // Recompute hashes under the input's own declared suite, so an
+        // input already built with `with_hash_suite` stays self-consistent.
+        let suite = input.hash_suite;
+        input.debtor_hash = suite.field_hash(canonicalize_json(&input.debtor_data).as_bytes());
+        input.creditor_hash = suite.field_hash(canonicalize_json(&input.creditor_data).as_bytes());
+        input.currency_hash = suite.field_hash(input.currency.as_bytes());
 
         // Recreate Merkle tree
         let amount_bytes = input.amount_value.to_be_bytes();
@@ -258,7 +424,7 @@ impl PaymentInstructionGenerator {
             (expiry_bytes.as_slice(), 5u8),
         ];
 
-        let tree = MerkleTree::new(tree_data);
+        let tree = MerkleTree::new_with_suite(tree_data, suite);
         input.root = tree.root();
 
         // Regenerate proofs
@@ -280,6 +446,125 @@ impl PaymentInstructionGenerator {
         input.expiry_proof_directions = expiry_proof.directions;
     }
 
+    /// Like `regenerate_merkle_proofs`, but leaves are hashed through a
+    /// compile-time-fixed [`LeafHasher`](crate::crypto_utils::LeafHasher)
+    /// instead of `suite.leaf_hash`'s bare tag byte, binding each field's
+    /// semantic role into its commitment (see
+    /// [`PersonalizedBlake2bLeafHasher`](crate::crypto_utils::PersonalizedBlake2bLeafHasher)).
+    /// Existing callers that never opt into this stay on `regenerate_merkle_proofs`
+    /// and keep today's roots; this is purely additive.
+    pub fn regenerate_merkle_proofs_with_leaf_hasher<L: LeafHasher>(&mut self, input: &mut PaymentInstructionInput) {
+        let suite = input.hash_suite;
+        input.debtor_hash = suite.field_hash(canonicalize_json(&input.debtor_data).as_bytes());
+        input.creditor_hash = suite.field_hash(canonicalize_json(&input.creditor_data).as_bytes());
+        input.currency_hash = suite.field_hash(input.currency.as_bytes());
+
+        let amount_bytes = input.amount_value.to_be_bytes();
+        let expiry_bytes = input.expiry.to_be_bytes();
+
+        let tree_data = vec![
+            (input.debtor_hash.as_slice(), 1u8),
+            (input.creditor_hash.as_slice(), 2u8),
+            (amount_bytes.as_slice(), 3u8),
+            (input.currency_hash.as_slice(), 4u8),
+            (expiry_bytes.as_slice(), 5u8),
+        ];
+
+        let tree = MerkleTree::new_with_leaf_hasher::<L>(tree_data, suite);
+        input.root = tree.root();
+
+        let debtor_proof = tree.generate_proof(0).unwrap();
+        let creditor_proof = tree.generate_proof(1).unwrap();
+        let amount_proof = tree.generate_proof(2).unwrap();
+        let currency_proof = tree.generate_proof(3).unwrap();
+        let expiry_proof = tree.generate_proof(4).unwrap();
+
+        input.debtor_proof_siblings = debtor_proof.siblings;
+        input.debtor_proof_directions = debtor_proof.directions;
+        input.creditor_proof_siblings = creditor_proof.siblings;
+        input.creditor_proof_directions = creditor_proof.directions;
+        input.amount_proof_siblings = amount_proof.siblings;
+        input.amount_proof_directions = amount_proof.directions;
+        input.currency_proof_siblings = currency_proof.siblings;
+        input.currency_proof_directions = currency_proof.directions;
+        input.expiry_proof_siblings = expiry_proof.siblings;
+        input.expiry_proof_directions = expiry_proof.directions;
+    }
+
+    /// Refresh exactly one field's `*_proof_siblings`/`*_proof_directions`
+    /// (and, for hashed fields, its public `*_hash`) after that field's data
+    /// has already been mutated on `input`, without recomputing the other
+    /// four fields' hashes the way `regenerate_merkle_proofs` always does.
+    /// Builds an [`IncrementalWitness`](crate::incremental_witness::IncrementalWitness)
+    /// from the five cached leaf values and updates only the changed field's
+    /// leaf and root path in it. The witness is built afresh on every call,
+    /// so each call still hashes the whole five-leaf tree once. The other
+    /// four fields' stored proofs are not updated, so they no longer reach
+    /// the new root whenever the root changes.
+    pub fn refresh_field(&self, input: &mut PaymentInstructionInput, field: PaymentField) {
+        let suite = input.hash_suite;
+
+        // The other four fields' leaves, built from their already-cached
+        // hashes/bytes rather than re-canonicalizing or re-hashing them.
+        let leaves = vec![
+            suite.leaf_hash(input.debtor_hash.as_slice(), 1u8),
+            suite.leaf_hash(input.creditor_hash.as_slice(), 2u8),
+            suite.leaf_hash(input.amount_value.to_be_bytes().as_slice(), 3u8),
+            suite.leaf_hash(input.currency_hash.as_slice(), 4u8),
+            suite.leaf_hash(input.expiry.to_be_bytes().as_slice(), 5u8),
+        ];
+        let mut witness = IncrementalWitness::build(leaves, suite);
+
+        let new_leaf = match field {
+            PaymentField::Debtor => {
+                input.debtor_hash = suite.field_hash(canonicalize_json(&input.debtor_data).as_bytes());
+                suite.leaf_hash(input.debtor_hash.as_slice(), 1u8)
+            }
+            PaymentField::Creditor => {
+                input.creditor_hash = suite.field_hash(canonicalize_json(&input.creditor_data).as_bytes());
+                suite.leaf_hash(input.creditor_hash.as_slice(), 2u8)
+            }
+            PaymentField::Amount => suite.leaf_hash(input.amount_value.to_be_bytes().as_slice(), 3u8),
+            PaymentField::Currency => {
+                input.currency_hash = suite.field_hash(input.currency.as_bytes());
+                suite.leaf_hash(input.currency_hash.as_slice(), 4u8)
+            }
+            PaymentField::Expiry => suite.leaf_hash(input.expiry.to_be_bytes().as_slice(), 5u8),
+        };
+
+        let position = field as usize;
+        witness
+            .update_leaf(position, new_leaf)
+            .expect("field position is always within the fixed five-leaf tree");
+        input.root = witness.root();
+
+        let proof = witness
+            .path_for(position)
+            .expect("field position is always within the fixed five-leaf tree");
+        match field {
+            PaymentField::Debtor => {
+                input.debtor_proof_siblings = proof.siblings;
+                input.debtor_proof_directions = proof.directions;
+            }
+            PaymentField::Creditor => {
+                input.creditor_proof_siblings = proof.siblings;
+                input.creditor_proof_directions = proof.directions;
+            }
+            PaymentField::Amount => {
+                input.amount_proof_siblings = proof.siblings;
+                input.amount_proof_directions = proof.directions;
+            }
+            PaymentField::Currency => {
+                input.currency_proof_siblings = proof.siblings;
+                input.currency_proof_directions = proof.directions;
+            }
+            PaymentField::Expiry => {
+                input.expiry_proof_siblings = proof.siblings;
+                input.expiry_proof_directions = proof.directions;
+            }
+        }
+    }
+
     /// Generate a valid payment instruction input using proper ISO 20022 format
     pub fn generate_payment_instruction_input(&mut self) -> PaymentInstructionInput {
         // Generate realistic payment instruction data based on sample files
@@ -309,9 +594,9 @@ impl PaymentInstructionGenerator {
         let expiry = 20250430u64; // Convert to YYYYMMDD format
 
         // Compute hashes
-        let debtor_hash = keccak256(canonicalize_json(&debtor_data).as_bytes());
-        let creditor_hash = keccak256(canonicalize_json(&creditor_data).as_bytes());
-        let currency_hash = keccak256(currency.as_bytes());
+        let debtor_hash = self.hash_suite.field_hash(canonicalize_json(&debtor_data).as_bytes());
+        let creditor_hash = self.hash_suite.field_hash(canonicalize_json(&creditor_data).as_bytes());
+        let currency_hash = self.hash_suite.field_hash(currency.as_bytes());
 
         // Create Merkle tree with all fields
         let amount_bytes = amount_value.to_be_bytes();
@@ -325,7 +610,7 @@ impl PaymentInstructionGenerator {
             (expiry_bytes.as_slice(), 5u8),
         ];
 
-        let tree = MerkleTree::new(tree_data);
+        let tree = MerkleTree::new_with_suite(tree_data, self.hash_suite);
         let root = tree.root();
 
         // Generate proofs for each field
@@ -358,6 +643,8 @@ impl PaymentInstructionGenerator {
             currency_proof_directions: currency_proof.directions,
             expiry_proof_siblings: expiry_proof.siblings,
             expiry_proof_directions: expiry_proof.directions,
+            hash_suite: self.hash_suite,
+            encrypted_payload: None,
         }
     }
 
@@ -446,6 +733,37 @@ impl PaymentInstructionGenerator {
         self.create_payment_instruction_input(debtor_data, creditor_data, 123456, "SGD", "2025-04-29")
     }
 
+    /// Build a `PaymentInstructionInput` directly from a pain.001 XML
+    /// message instead of hand-written sample JSON.
+    pub fn generate_from_pain001_xml(
+        &mut self,
+        xml: &str,
+    ) -> Result<PaymentInstructionInput, String> {
+        let fields = parse_pain001(xml)?;
+        Ok(self.create_payment_instruction_input(
+            fields.debtor_data,
+            fields.creditor_data,
+            fields.amount_milli,
+            &fields.currency,
+            &fields.execution_date,
+        ))
+    }
+
+    /// Build a `PaymentInstructionInput` directly from a production pain.001
+    /// message, either XML or the JSON form, with structured errors for
+    /// missing mandatory fields, malformed amounts/dates, and unsupported
+    /// currencies instead of `generate_from_pain001_xml`'s plain `String`.
+    pub fn from_iso20022(&mut self, msg: &str) -> Result<PaymentInstructionInput, ParseError> {
+        let fields = parse_iso20022(msg)?;
+        Ok(self.create_payment_instruction_input(
+            fields.debtor_data,
+            fields.creditor_data,
+            fields.amount_milli,
+            &fields.currency,
+            &fields.execution_date,
+        ))
+    }
+
     fn create_payment_instruction_input(
         &mut self,
         debtor_data: String,
@@ -463,9 +781,9 @@ impl PaymentInstructionGenerator {
         let expiry = exec_date.replace("-", "").parse::<u64>().unwrap();
 
         // Compute hashes
-        let debtor_hash = keccak256(canonicalize_json(&debtor_data).as_bytes());
-        let creditor_hash = keccak256(canonicalize_json(&creditor_data).as_bytes());
-        let currency_hash = keccak256(currency.as_bytes());
+        let debtor_hash = self.hash_suite.field_hash(canonicalize_json(&debtor_data).as_bytes());
+        let creditor_hash = self.hash_suite.field_hash(canonicalize_json(&creditor_data).as_bytes());
+        let currency_hash = self.hash_suite.field_hash(currency.as_bytes());
 
         // Create Merkle tree
         let amount_bytes = amount_milli.to_be_bytes();
@@ -479,7 +797,7 @@ impl PaymentInstructionGenerator {
             (expiry_bytes.as_slice(), 5u8),
         ];
 
-        let tree = MerkleTree::new(tree_data);
+        let tree = MerkleTree::new_with_suite(tree_data, self.hash_suite);
         let root = tree.root();
 
         // Generate proofs
@@ -512,6 +830,8 @@ impl PaymentInstructionGenerator {
             currency_proof_directions: currency_proof.directions,
             expiry_proof_siblings: expiry_proof.siblings,
             expiry_proof_directions: expiry_proof.directions,
+            hash_suite: self.hash_suite,
+            encrypted_payload: None,
         }
     }
 }
@@ -607,4 +927,269 @@ mod tests {
         assert!(edge_cases[4].debtor_data.contains("José"));
         assert!(edge_cases[4].creditor_data.contains("李"));
     }
+
+    #[test]
+    fn test_with_hash_suite_commits_declared_suite() {
+        let mut generator = PaymentInstructionGenerator::with_hash_suite(HashSuite::KeccakOnly);
+        let input = generator.generate_valid_input();
+        assert_eq!(input.hash_suite, HashSuite::KeccakOnly);
+
+        let expected_debtor_hash =
+            HashSuite::KeccakOnly.field_hash(canonicalize_json(&input.debtor_data).as_bytes());
+        assert_eq!(input.debtor_hash, expected_debtor_hash);
+    }
+
+    #[test]
+    fn test_output_encode_decode_roundtrip() {
+        let mut generator = PaymentInstructionGenerator::new();
+        let input = generator.generate_valid_input();
+        let output = PaymentInstructionOutput {
+            root: input.root,
+            debtor_hash: input.debtor_hash,
+            creditor_hash: input.creditor_hash,
+            min_amount_milli: input.min_amount_milli,
+            max_amount_milli: input.max_amount_milli,
+            currency_hash: input.currency_hash,
+            expiry: input.expiry,
+            hash_suite: input.hash_suite,
+        };
+
+        let encoded = output.encode();
+        assert!(encoded.starts_with("pi1"));
+
+        let decoded = PaymentInstructionOutput::decode(&encoded).unwrap();
+        assert_eq!(decoded.root, output.root);
+        assert_eq!(decoded.min_amount_milli, output.min_amount_milli);
+        assert_eq!(decoded.expiry, output.expiry);
+        assert_eq!(decoded.hash_suite, output.hash_suite);
+    }
+
+    #[test]
+    fn test_output_decode_rejects_corrupted_checksum() {
+        let output = PaymentInstructionOutput {
+            root: [1u8; 32],
+            debtor_hash: [2u8; 32],
+            creditor_hash: [3u8; 32],
+            min_amount_milli: 1000,
+            max_amount_milli: 2000,
+            currency_hash: [4u8; 32],
+            expiry: 20241231,
+            hash_suite: HashSuite::default(),
+        };
+        let mut encoded = output.encode().into_bytes();
+        let last = encoded.len() - 1;
+        encoded[last] = if encoded[last] == b'q' { b'p' } else { b'q' };
+        let corrupted = String::from_utf8(encoded).unwrap();
+
+        assert!(PaymentInstructionOutput::decode(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_refresh_field_matches_full_regenerate() {
+        let mut generator = PaymentInstructionGenerator::new();
+        let mut incremental = generator.generate_valid_input();
+        let mut full_rebuild = incremental.clone();
+
+        incremental.amount_value += 1;
+        full_rebuild.amount_value = incremental.amount_value;
+
+        generator.refresh_field(&mut incremental, PaymentField::Amount);
+        generator.regenerate_merkle_proofs(&mut full_rebuild);
+
+        assert_eq!(incremental.root, full_rebuild.root);
+        assert_eq!(incremental.amount_proof_siblings, full_rebuild.amount_proof_siblings);
+        assert_eq!(incremental.amount_proof_directions, full_rebuild.amount_proof_directions);
+        // The other four fields' proofs are untouched by a single-field refresh.
+        assert_eq!(incremental.debtor_proof_siblings, full_rebuild.debtor_proof_siblings);
+    }
+
+    #[test]
+    fn test_refresh_field_leaves_other_fields_unchanged() {
+        let mut generator = PaymentInstructionGenerator::new();
+        let mut input = generator.generate_valid_input();
+        let original_creditor_hash = input.creditor_hash;
+        let original_creditor_proof = input.creditor_proof_siblings.clone();
+
+        input.debtor_data = r#"{"n":"Someone Else"}"#.to_string();
+        generator.refresh_field(&mut input, PaymentField::Debtor);
+
+        assert_eq!(input.creditor_hash, original_creditor_hash);
+        assert_eq!(input.creditor_proof_siblings, original_creditor_proof);
+    }
+
+    #[test]
+    fn test_regenerate_with_explicit_default_leaf_hasher_matches_default() {
+        use crate::crypto_utils::DefaultLeafHasher;
+
+        let mut generator = PaymentInstructionGenerator::new();
+        let mut via_leaf_hasher = generator.generate_valid_input();
+        let mut via_default = via_leaf_hasher.clone();
+
+        generator.regenerate_merkle_proofs_with_leaf_hasher::<DefaultLeafHasher>(&mut via_leaf_hasher);
+        generator.regenerate_merkle_proofs(&mut via_default);
+
+        assert_eq!(via_leaf_hasher.root, via_default.root);
+    }
+
+    #[test]
+    fn test_regenerate_with_leaf_hasher_personalized_blake2b_changes_root_and_verifies() {
+        use crate::crypto_utils::PersonalizedBlake2bLeafHasher;
+        use crate::merkle_tree::MerkleTree;
+
+        let mut generator = PaymentInstructionGenerator::new();
+        let mut input = generator.generate_valid_input();
+        let default_root = input.root;
+
+        generator.regenerate_merkle_proofs_with_leaf_hasher::<PersonalizedBlake2bLeafHasher>(&mut input);
+
+        assert_ne!(input.root, default_root);
+        let debtor_leaf = PersonalizedBlake2bLeafHasher::hash_leaf(input.debtor_hash.as_slice(), 1u8);
+        let debtor_proof = crate::merkle_tree::MerkleProof {
+            siblings: input.debtor_proof_siblings.clone(),
+            directions: input.debtor_proof_directions.clone(),
+        };
+        assert!(MerkleTree::verify_proof(&debtor_leaf, &debtor_proof, &input.root));
+    }
+
+    #[test]
+    fn test_encrypt_then_decrypt_private_data_round_trips() {
+        use x25519_dalek::{PublicKey, StaticSecret};
+
+        let recipient_sk = StaticSecret::random_from_rng(rand::thread_rng());
+        let recipient_pk = PublicKey::from(&recipient_sk);
+
+        let mut generator = PaymentInstructionGenerator::new();
+        let mut input = generator.generate_valid_input();
+        let original_debtor_data = input.debtor_data.clone();
+        let original_creditor_data = input.creditor_data.clone();
+
+        input.encrypt_private_data(&recipient_pk);
+        assert!(input.encrypted_payload.is_some());
+
+        let (debtor_data, creditor_data) = input.decrypt_private_data(&recipient_sk).unwrap();
+        assert_eq!(debtor_data, canonicalize_json(&original_debtor_data));
+        assert_eq!(creditor_data, canonicalize_json(&original_creditor_data));
+    }
+
+    #[test]
+    fn test_encrypt_private_data_does_not_change_public_hashes_or_root() {
+        use x25519_dalek::{PublicKey, StaticSecret};
+
+        let recipient_sk = StaticSecret::random_from_rng(rand::thread_rng());
+        let recipient_pk = PublicKey::from(&recipient_sk);
+
+        let mut generator = PaymentInstructionGenerator::new();
+        let mut input = generator.generate_valid_input();
+        let root_before = input.root;
+        let debtor_hash_before = input.debtor_hash;
+
+        input.encrypt_private_data(&recipient_pk);
+
+        assert_eq!(input.root, root_before);
+        assert_eq!(input.debtor_hash, debtor_hash_before);
+    }
+
+    #[test]
+    fn test_decrypt_private_data_without_encrypting_first_errors() {
+        use x25519_dalek::StaticSecret;
+
+        let recipient_sk = StaticSecret::random_from_rng(rand::thread_rng());
+        let generator_input = PaymentInstructionGenerator::new().generate_valid_input();
+
+        assert!(generator_input.decrypt_private_data(&recipient_sk).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_private_data_with_wrong_key_errors() {
+        use x25519_dalek::{PublicKey, StaticSecret};
+
+        let recipient_sk = StaticSecret::random_from_rng(rand::thread_rng());
+        let recipient_pk = PublicKey::from(&recipient_sk);
+        let wrong_sk = StaticSecret::random_from_rng(rand::thread_rng());
+
+        let mut input = PaymentInstructionGenerator::new().generate_valid_input();
+        input.encrypt_private_data(&recipient_pk);
+
+        assert!(input.decrypt_private_data(&wrong_sk).is_err());
+    }
+
+    const SAMPLE_PAIN001_XML: &str = r#"
+        <Document>
+          <CstmrCdtTrfInitn>
+            <PmtInf>
+              <ReqdExctnDt>2025-04-30</ReqdExctnDt>
+              <Dbtr><Nm>Acme Corporation</Nm></Dbtr>
+              <CdtTrfTxInf>
+                <Amt><InstdAmt Ccy="USD">1250.75</InstdAmt></Amt>
+                <Cdtr><Nm>Bob's Supplies</Nm></Cdtr>
+              </CdtTrfTxInf>
+            </PmtInf>
+          </CstmrCdtTrfInitn>
+        </Document>
+    "#;
+
+    const SAMPLE_PAIN001_JSON: &str = r#"{
+        "Dbtr": {"Nm": "Acme Corporation"},
+        "Cdtr": {"Nm": "Bob's Supplies"},
+        "InstdAmt": {"Value": 1250.75, "Ccy": "USD"},
+        "ReqdExctnDt": "2025-04-30"
+    }"#;
+
+    #[test]
+    fn test_from_iso20022_parses_xml_with_valid_merkle_proofs() {
+        let mut generator = PaymentInstructionGenerator::new();
+        let input = generator.from_iso20022(SAMPLE_PAIN001_XML).unwrap();
+
+        assert_eq!(input.currency, "USD");
+        assert_eq!(input.amount_value, 125075);
+        assert_eq!(input.expiry, 20250430);
+
+        let debtor_leaf = input.hash_suite.leaf_hash(input.debtor_hash.as_slice(), 1u8);
+        let debtor_proof = crate::merkle_tree::MerkleProof {
+            siblings: input.debtor_proof_siblings.clone(),
+            directions: input.debtor_proof_directions.clone(),
+        };
+        assert!(crate::merkle_tree::MerkleTree::verify_proof_with_suite(
+            &debtor_leaf,
+            &debtor_proof,
+            &input.root,
+            input.hash_suite
+        ));
+    }
+
+    #[test]
+    fn test_from_iso20022_parses_json_form() {
+        let mut generator = PaymentInstructionGenerator::new();
+        let input = generator.from_iso20022(SAMPLE_PAIN001_JSON).unwrap();
+
+        assert_eq!(input.currency, "USD");
+        assert_eq!(input.amount_value, 125075);
+        assert_eq!(input.expiry, 20250430);
+    }
+
+    #[test]
+    fn test_from_iso20022_unsupported_currency_returns_structured_error() {
+        let bad_currency = r#"{
+            "Dbtr": {"Nm": "Acme"}, "Cdtr": {"Nm": "Bob"},
+            "InstdAmt": {"Value": 10.0, "Ccy": "dollars"},
+            "ReqdExctnDt": "2025-04-30"
+        }"#;
+        let mut generator = PaymentInstructionGenerator::new();
+
+        assert_eq!(
+            generator.from_iso20022(bad_currency).unwrap_err(),
+            ParseError::UnsupportedCurrency("dollars".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_iso20022_missing_field_returns_structured_error() {
+        let truncated = "<Document><CstmrCdtTrfInitn></CstmrCdtTrfInitn></Document>";
+        let mut generator = PaymentInstructionGenerator::new();
+
+        assert_eq!(
+            generator.from_iso20022(truncated).unwrap_err(),
+            ParseError::MissingField("Dbtr/Nm")
+        );
+    }
 }
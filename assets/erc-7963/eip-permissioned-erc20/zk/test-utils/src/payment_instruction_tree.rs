@@ -0,0 +1,293 @@
+use crate::crypto_utils::HashSuite;
+use crate::merkle_tree::MerkleProof;
+use std::collections::HashMap;
+
+/// Depth of the tree `PaymentInstructionTree` always builds, regardless of
+/// how many of the five fields are actually populated. Fixed so a verifier
+/// never has to guess how many padding levels a builder used — both sides
+/// hard-code `TREE_DEPTH` rather than deriving it from leaf count.
+pub const TREE_DEPTH: usize = 3;
+
+/// Number of leaf slots at `TREE_DEPTH` (`2^TREE_DEPTH`). The five tagged
+/// payment-instruction fields occupy the first five slots; the remaining
+/// slots are padded with [`PADDING_TAG`] leaves.
+const LEAF_CAPACITY: usize = 1 << TREE_DEPTH;
+
+/// Leaf tags, matching `PaymentInstructionGenerator`/`verify_merkle_proof`'s
+/// existing convention (debtor=1, creditor=2, amount=3, currency=4,
+/// expiry=5) so builder output round-trips through the existing
+/// verification path unchanged.
+pub const DEBTOR_TAG: u8 = 1;
+pub const CREDITOR_TAG: u8 = 2;
+pub const AMOUNT_TAG: u8 = 3;
+pub const CURRENCY_TAG: u8 = 4;
+pub const EXPIRY_TAG: u8 = 5;
+
+/// Tag for the constant empty-leaf hash padding slots are filled with.
+/// Distinct from every real field tag (1-5) so a padding leaf can never be
+/// mistaken for (or collide with) a real field's leaf hash.
+const PADDING_TAG: u8 = 0;
+
+const FIELD_TAGS: [u8; 5] = [
+    DEBTOR_TAG,
+    CREDITOR_TAG,
+    AMOUNT_TAG,
+    CURRENCY_TAG,
+    EXPIRY_TAG,
+];
+
+fn tag_to_leaf_index(tag: u8) -> Option<usize> {
+    FIELD_TAGS.iter().position(|&t| t == tag)
+}
+
+/// A Merkle proof for one field, bundled with the tag it was proven under so
+/// [`verify_batch`] can report which field failed.
+#[derive(Debug, Clone)]
+pub struct FieldProof {
+    pub tag: u8,
+    pub leaf: [u8; 32],
+    pub proof: MerkleProof,
+}
+
+/// Builds the fixed-depth Merkle tree a `PaymentInstructionInput` is proven
+/// against: the five tagged fields (debtor=1, creditor=2, amount=3,
+/// currency=4, expiry=5) padded up to `LEAF_CAPACITY` with a constant
+/// empty-leaf hash, so every instance of this tree has the same shape no
+/// matter which fields a given payment instruction actually uses. Leaf
+/// tagging and node-hash ordering are identical to
+/// `guest::verify_merkle_proof`, so the `(siblings, directions)` pairs
+/// emitted by [`Self::proof_for`] populate `PaymentInstructionInput`
+/// directly.
+#[derive(Debug, Clone)]
+pub struct PaymentInstructionTree {
+    suite: HashSuite,
+    leaves: Vec<[u8; 32]>,
+    tree: Vec<Vec<[u8; 32]>>,
+}
+
+impl PaymentInstructionTree {
+    /// Build the tree under the default `HashSuite::PoseidonKeccak` suite.
+    pub fn build(
+        debtor_hash: [u8; 32],
+        creditor_hash: [u8; 32],
+        amount_value: u64,
+        currency_hash: [u8; 32],
+        expiry: u64,
+    ) -> Self {
+        Self::build_with_suite(
+            debtor_hash,
+            creditor_hash,
+            amount_value,
+            currency_hash,
+            expiry,
+            HashSuite::default(),
+        )
+    }
+
+    /// Build the tree, hashing leaves and internal nodes under `suite`
+    /// instead of the default pairing.
+    pub fn build_with_suite(
+        debtor_hash: [u8; 32],
+        creditor_hash: [u8; 32],
+        amount_value: u64,
+        currency_hash: [u8; 32],
+        expiry: u64,
+        suite: HashSuite,
+    ) -> Self {
+        let amount_bytes = amount_value.to_be_bytes();
+        let expiry_bytes = expiry.to_be_bytes();
+
+        let mut leaves: Vec<[u8; 32]> = vec![
+            suite.leaf_hash(&debtor_hash, DEBTOR_TAG),
+            suite.leaf_hash(&creditor_hash, CREDITOR_TAG),
+            suite.leaf_hash(&amount_bytes, AMOUNT_TAG),
+            suite.leaf_hash(&currency_hash, CURRENCY_TAG),
+            suite.leaf_hash(&expiry_bytes, EXPIRY_TAG),
+        ];
+
+        let empty_leaf = suite.leaf_hash(&[], PADDING_TAG);
+        leaves.resize(LEAF_CAPACITY, empty_leaf);
+
+        let mut tree = vec![leaves.clone()];
+        let mut current_level = leaves.clone();
+        while current_level.len() > 1 {
+            let next_level: Vec<[u8; 32]> = current_level
+                .chunks(2)
+                .map(|chunk| suite.node_hash(&chunk[0], &chunk[1]))
+                .collect();
+            tree.push(next_level.clone());
+            current_level = next_level;
+        }
+
+        Self {
+            suite,
+            leaves,
+            tree,
+        }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.last().unwrap()[0]
+    }
+
+    /// Generate the `(siblings, directions)` proof for the field tagged
+    /// `tag`, in the exact shape `PaymentInstructionInput`'s
+    /// `*_proof_siblings`/`*_proof_directions` fields expect.
+    pub fn proof_for(&self, tag: u8) -> Result<FieldProof, String> {
+        let leaf_index = tag_to_leaf_index(tag).ok_or_else(|| format!("unknown field tag: {}", tag))?;
+
+        let mut siblings = Vec::with_capacity(TREE_DEPTH);
+        let mut directions = Vec::with_capacity(TREE_DEPTH);
+        let mut current_index = leaf_index;
+
+        for level in 0..self.tree.len() - 1 {
+            let sibling_index = current_index ^ 1;
+            siblings.push(self.tree[level][sibling_index]);
+            directions.push((current_index % 2) as u8);
+            current_index /= 2;
+        }
+
+        Ok(FieldProof {
+            tag,
+            leaf: self.leaves[leaf_index],
+            proof: MerkleProof {
+                siblings,
+                directions,
+            },
+        })
+    }
+
+    /// Generate proofs for all five fields in tag order
+    /// (debtor, creditor, amount, currency, expiry).
+    pub fn all_proofs(&self) -> Vec<FieldProof> {
+        FIELD_TAGS
+            .iter()
+            .map(|&tag| self.proof_for(tag).expect("every FIELD_TAGS entry has a leaf"))
+            .collect()
+    }
+}
+
+/// Verify all five field proofs against `root` in a single pass, caching
+/// each internal node the first time it's computed so fields whose paths
+/// share an ancestor (any two of debtor/creditor, or amount/currency, since
+/// they're leaf-adjacent at `TREE_DEPTH`) reuse that node instead of
+/// recomputing it. Returns the tag of the first field whose path doesn't
+/// reach `root`, or `Ok(())` if all five check out.
+pub fn verify_batch(proofs: &[FieldProof], root: &[u8; 32], suite: HashSuite) -> Result<(), u8> {
+    let mut node_cache: HashMap<(usize, usize), [u8; 32]> = HashMap::new();
+
+    for field in proofs {
+        let leaf_index = match tag_to_leaf_index(field.tag) {
+            Some(index) => index,
+            None => return Err(field.tag),
+        };
+        node_cache.insert((0, leaf_index), field.leaf);
+
+        let mut current_index = leaf_index;
+        let mut current = field.leaf;
+
+        for (level, (sibling, direction)) in field
+            .proof
+            .siblings
+            .iter()
+            .zip(field.proof.directions.iter())
+            .enumerate()
+        {
+            current = if *direction == 0 {
+                suite.node_hash(&current, sibling)
+            } else {
+                suite.node_hash(sibling, &current)
+            };
+            current_index /= 2;
+
+            // Reuse whatever another field already computed for this
+            // ancestor rather than trusting our own recomputation blindly:
+            // if the two disagree, this field's path is inconsistent with
+            // one already verified, which is itself a failure.
+            let node_level = level + 1;
+            if let Some(&cached) = node_cache.get(&(node_level, current_index)) {
+                if cached != current {
+                    return Err(field.tag);
+                }
+            }
+            node_cache.insert((node_level, current_index), current);
+        }
+
+        if current != *root {
+            return Err(field.tag);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tree() -> PaymentInstructionTree {
+        PaymentInstructionTree::build(
+            [1u8; 32],
+            [2u8; 32],
+            5000,
+            [3u8; 32],
+            20241231,
+        )
+    }
+
+    #[test]
+    fn test_tree_has_fixed_depth_regardless_of_field_count() {
+        let tree = sample_tree();
+        assert_eq!(tree.tree.len(), TREE_DEPTH + 1);
+        assert_eq!(tree.leaves.len(), LEAF_CAPACITY);
+    }
+
+    #[test]
+    fn test_padding_leaves_are_identical_constant_hash() {
+        let tree = sample_tree();
+        let padding = &tree.leaves[5..];
+        assert!(padding.iter().all(|leaf| *leaf == padding[0]));
+    }
+
+    #[test]
+    fn test_each_field_proof_round_trips_through_merkle_proof_verify() {
+        let tree = sample_tree();
+        let root = tree.root();
+
+        for field in tree.all_proofs() {
+            assert!(crate::merkle_tree::MerkleTree::verify_proof(
+                &field.leaf,
+                &field.proof,
+                &root
+            ));
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_accepts_all_valid_proofs() {
+        let tree = sample_tree();
+        let root = tree.root();
+        let proofs = tree.all_proofs();
+        assert_eq!(verify_batch(&proofs, &root, HashSuite::default()), Ok(()));
+    }
+
+    #[test]
+    fn test_verify_batch_reports_the_tampered_field() {
+        let tree = sample_tree();
+        let root = tree.root();
+        let mut proofs = tree.all_proofs();
+        proofs[2].leaf = [0xAA; 32]; // amount (tag 3) tampered
+
+        assert_eq!(verify_batch(&proofs, &root, HashSuite::default()), Err(AMOUNT_TAG));
+    }
+
+    #[test]
+    fn test_verify_batch_rejects_unknown_tag() {
+        let tree = sample_tree();
+        let root = tree.root();
+        let mut proofs = tree.all_proofs();
+        proofs[0].tag = 99;
+
+        assert_eq!(verify_batch(&proofs, &root, HashSuite::default()), Err(99));
+    }
+}
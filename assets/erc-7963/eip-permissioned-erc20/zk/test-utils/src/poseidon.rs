@@ -0,0 +1,316 @@
+//! A from-scratch Poseidon sponge over a small prime field, giving
+//! `crypto_utils::PoseidonHasher` a real ZK-friendly backend instead of the
+//! SHA256 placeholder `poseidon_hash` used since this crate's `HashSuite`
+//! was introduced. Poseidon's only nonlinearity is a field multiplication
+//! (`x^5`), which costs a handful of constraints in an arithmetic circuit
+//! versus the thousands a bit-oriented hash like keccak/SHA256 needs — the
+//! entire point of using it for internal Merkle node hashing inside a zk
+//! guest.
+//!
+//! This is deliberately not a production Poseidon instantiation: the prime,
+//! round counts, round constants and MDS matrix below are a plausible but
+//! unaudited parameter set generated with a fixed deterministic PRNG rather
+//! than the usual Grobner-basis security analysis real deployments run.
+//! Swap in audited parameters (or a crate like `poseidon-rs`) before this
+//! ever guards real funds.
+
+/// A 61-bit Mersenne prime, chosen so field elements fit in a `u64` and
+/// products fit in a `u128` without any extra reduction tricks.
+const PRIME: u64 = (1u64 << 61) - 1;
+
+/// Sponge width: 2 rate lanes (what gets absorbed/squeezed per permutation)
+/// plus 1 capacity lane (never exposed, carries the security margin).
+const WIDTH: usize = 3;
+
+/// Rounds with the full `x^5` S-box applied to every lane, split evenly
+/// before and after the partial rounds (the standard Poseidon round
+/// schedule: R_F/2 full, R_P partial, R_F/2 full).
+const FULL_ROUNDS: usize = 8;
+
+/// Rounds with `x^5` applied only to lane 0 — partial rounds are cheaper
+/// per round and make up the bulk of Poseidon's security margin.
+const PARTIAL_ROUNDS: usize = 22;
+
+fn field_add(a: u64, b: u64) -> u64 {
+    ((a as u128 + b as u128) % PRIME as u128) as u64
+}
+
+fn field_mul(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) % PRIME as u128) as u64
+}
+
+fn field_pow5(a: u64) -> u64 {
+    let a2 = field_mul(a, a);
+    let a4 = field_mul(a2, a2);
+    field_mul(a4, a)
+}
+
+fn field_pow(mut base: u64, mut exponent: u64) -> u64 {
+    base %= PRIME;
+    let mut result = 1u64;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = field_mul(result, base);
+        }
+        base = field_mul(base, base);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Modular inverse via Fermat's little theorem (`PRIME` is prime).
+fn field_inverse(a: u64) -> u64 {
+    field_pow(a, PRIME - 2)
+}
+
+/// A splitmix64 step, used only to deterministically generate this module's
+/// round constants and MDS matrix from a fixed seed — not part of the
+/// Poseidon permutation itself.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+fn round_constants() -> Vec<[u64; WIDTH]> {
+    let mut seed = 0x504F5345_49444F4E_u64; // arbitrary fixed seed ("POSEIDON" in hex-ish ASCII)
+    (0..FULL_ROUNDS + PARTIAL_ROUNDS)
+        .map(|_| core::array::from_fn(|_| splitmix64(&mut seed) % PRIME))
+        .collect()
+}
+
+/// A Cauchy matrix (`M[i][j] = 1 / (x_i + y_j)`), the standard way to build
+/// an MDS matrix: any square submatrix of a Cauchy matrix is itself
+/// invertible, which is exactly the "maximum distance separable" property
+/// Poseidon's mixing layer needs.
+fn mds_matrix() -> [[u64; WIDTH]; WIDTH] {
+    let mut seed = 0x4D44535F4D415452_u64; // arbitrary fixed seed ("MDS_MATR")
+    let xs: [u64; WIDTH] = core::array::from_fn(|_| splitmix64(&mut seed) % PRIME);
+    let ys: [u64; WIDTH] = core::array::from_fn(|_| splitmix64(&mut seed) % PRIME);
+
+    core::array::from_fn(|i| core::array::from_fn(|j| field_inverse(field_add(xs[i], ys[j]))))
+}
+
+/// Run the Poseidon permutation: add round constants, apply the `x^5`
+/// S-box (all lanes in full rounds, lane 0 only in partial rounds), then
+/// mix lanes through the MDS matrix — repeated for `FULL_ROUNDS +
+/// PARTIAL_ROUNDS` rounds.
+fn permute(mut state: [u64; WIDTH]) -> [u64; WIDTH] {
+    let constants = round_constants();
+    let mds = mds_matrix();
+    let half_full = FULL_ROUNDS / 2;
+
+    for (round, round_constants) in constants.iter().enumerate() {
+        for (lane, constant) in state.iter_mut().zip(round_constants.iter()) {
+            *lane = field_add(*lane, *constant);
+        }
+
+        let is_full_round = round < half_full || round >= half_full + PARTIAL_ROUNDS;
+        if is_full_round {
+            for lane in state.iter_mut() {
+                *lane = field_pow5(*lane);
+            }
+        } else {
+            state[0] = field_pow5(state[0]);
+        }
+
+        state = core::array::from_fn(|i| {
+            (0..WIDTH).fold(0u64, |acc, j| field_add(acc, field_mul(mds[i][j], state[j])))
+        });
+    }
+
+    state
+}
+
+/// Fold a 32-byte hash into the 2 rate lanes Poseidon absorbs per
+/// permutation: split into four 8-byte limbs and XOR opposite limbs
+/// together, reducing each pair mod [`PRIME`].
+fn bytes32_to_field_pair(bytes: &[u8; 32]) -> [u64; 2] {
+    let limb = |offset: usize| -> u64 {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(&bytes[offset..offset + 8]);
+        u64::from_be_bytes(buf)
+    };
+    [(limb(0) ^ limb(16)) % PRIME, (limb(8) ^ limb(24)) % PRIME]
+}
+
+/// Squeeze 32 bytes out of `state` by taking lane 0 after each of 4
+/// permutations. Re-permuting before every 8-byte chunk (rather than
+/// draining both rate lanes before permuting again) trades a little
+/// throughput for a simpler, more obviously-correct squeeze loop — fine
+/// here since this module's cost profile is circuit constraints, not host
+/// wall-clock time.
+fn squeeze_32(mut state: [u64; WIDTH]) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    for chunk in output.chunks_mut(8) {
+        state = permute(state);
+        chunk.copy_from_slice(&state[0].to_be_bytes());
+    }
+    output
+}
+
+/// Hash a single field/leaf preimage, domain-separated by folding `tag`
+/// into the first absorbed lane so leaves with the same bytes but
+/// different tags (e.g. debtor vs. creditor) never collide.
+pub fn poseidon_leaf_hash(preimage: &[u8], tag: u8) -> [u8; 32] {
+    // Poseidon absorbs field elements, not arbitrary-length byte strings, so
+    // compress the preimage to a fixed 32 bytes first with the crate's
+    // existing keccak256 — the same compression `HashSuite::field_hash`
+    // applies under its keccak-based suites (`PoseidonKeccak`, `KeccakOnly`).
+    let digest = crate::hashing::keccak256(preimage);
+    let [e0, e1] = bytes32_to_field_pair(&digest);
+    let state = [field_add(e0, tag as u64), e1, 0];
+    squeeze_32(permute(state))
+}
+
+/// Hash two Merkle child nodes into their parent: absorb `left`, permute,
+/// absorb `right`, then squeeze — two absorptions for two 32-byte inputs,
+/// exactly the sponge construction's "absorb, absorb, ..., squeeze" shape.
+pub fn poseidon_node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let [l0, l1] = bytes32_to_field_pair(left);
+    let [r0, r1] = bytes32_to_field_pair(right);
+
+    let mut state = permute([l0, l1, 0]);
+    state[0] = field_add(state[0], r0);
+    state[1] = field_add(state[1], r1);
+
+    squeeze_32(state)
+}
+
+/// Prefix folded into `poseidon_node_hash_domain_separated`'s first
+/// absorbed lane, the Poseidon-sponge analogue of `poseidon_leaf_hash`'s
+/// `tag` fold: it exists purely to put internal-node hashes in a different
+/// domain from leaf hashes, so a 32-byte node hash can never be replayed
+/// as if it were a leaf (or vice versa) — the same second-preimage
+/// confusion RFC 6962 and `crate::hashing::keccak256_node_hash`'s
+/// `NODE_DOMAIN_TAG` guard against.
+const MERKLE_NODE_PREFIX: u64 = 0xFF;
+
+/// Domain-separated counterpart to `poseidon_node_hash`: identical sponge
+/// construction, except `MERKLE_NODE_PREFIX` is folded into the first
+/// absorbed lane before `left` is absorbed, the same way `poseidon_leaf_hash`
+/// folds a leaf's `tag` in. Plain `poseidon_node_hash` has no such prefix,
+/// so a value produced by it can collide in shape with a leaf hash; callers
+/// that need that guarantee closed should use this instead (see
+/// `MerkleTree::new_domain_separated`).
+pub fn poseidon_node_hash_domain_separated(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let [l0, l1] = bytes32_to_field_pair(left);
+    let [r0, r1] = bytes32_to_field_pair(right);
+
+    let mut state = permute([field_add(l0, MERKLE_NODE_PREFIX), l1, 0]);
+    state[0] = field_add(state[0], r0);
+    state[1] = field_add(state[1], r1);
+
+    squeeze_32(state)
+}
+
+/// Hash an arbitrary number of child nodes into their parent in one sponge
+/// pass: absorb each child's field pair in turn (permuting between
+/// absorptions), then squeeze. Generalizes `poseidon_node_hash`'s
+/// always-exactly-two-children case to the wider fan-in a configurable-arity
+/// tree needs, so a 4-ary or 8-ary `NaryMerkleTree` hashes all of a node's
+/// children in one invocation rather than folding them pairwise.
+pub fn poseidon_hash_many(children: &[[u8; 32]]) -> [u8; 32] {
+    let mut state = [0u64; WIDTH];
+    for child in children {
+        let [c0, c1] = bytes32_to_field_pair(child);
+        state = permute(state);
+        state[0] = field_add(state[0], c0);
+        state[1] = field_add(state[1], c1);
+    }
+    squeeze_32(permute(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaf_hash_is_deterministic() {
+        let preimage = b"debtor data";
+        assert_eq!(poseidon_leaf_hash(preimage, 1u8), poseidon_leaf_hash(preimage, 1u8));
+    }
+
+    #[test]
+    fn test_leaf_hash_is_tag_domain_separated() {
+        let preimage = b"debtor data";
+        assert_ne!(poseidon_leaf_hash(preimage, 1u8), poseidon_leaf_hash(preimage, 2u8));
+    }
+
+    #[test]
+    fn test_node_hash_is_deterministic() {
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+        assert_eq!(poseidon_node_hash(&left, &right), poseidon_node_hash(&left, &right));
+    }
+
+    #[test]
+    fn test_node_hash_is_order_sensitive() {
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+        assert_ne!(poseidon_node_hash(&left, &right), poseidon_node_hash(&right, &left));
+    }
+
+    #[test]
+    fn test_node_hash_differs_from_keccak_node_hash() {
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+        assert_ne!(
+            poseidon_node_hash(&left, &right),
+            crate::hashing::keccak256_node_hash(&left, &right)
+        );
+    }
+
+    #[test]
+    fn test_field_inverse_round_trips() {
+        let a = 123456789u64 % PRIME;
+        assert_eq!(field_mul(a, field_inverse(a)), 1);
+    }
+
+    #[test]
+    fn test_domain_separated_node_hash_differs_from_plain_node_hash() {
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+        assert_ne!(
+            poseidon_node_hash(&left, &right),
+            poseidon_node_hash_domain_separated(&left, &right)
+        );
+    }
+
+    #[test]
+    fn test_domain_separated_node_hash_is_deterministic_and_order_sensitive() {
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+        assert_eq!(
+            poseidon_node_hash_domain_separated(&left, &right),
+            poseidon_node_hash_domain_separated(&left, &right)
+        );
+        assert_ne!(
+            poseidon_node_hash_domain_separated(&left, &right),
+            poseidon_node_hash_domain_separated(&right, &left)
+        );
+    }
+
+    #[test]
+    fn test_hash_many_is_deterministic() {
+        let children = [[1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]];
+        assert_eq!(poseidon_hash_many(&children), poseidon_hash_many(&children));
+    }
+
+    #[test]
+    fn test_hash_many_is_order_sensitive() {
+        let forward = [[1u8; 32], [2u8; 32], [3u8; 32], [4u8; 32]];
+        let mut reversed = forward;
+        reversed.reverse();
+        assert_ne!(poseidon_hash_many(&forward), poseidon_hash_many(&reversed));
+    }
+
+    #[test]
+    fn test_hash_many_differs_by_child_count() {
+        let two = [[1u8; 32], [2u8; 32]];
+        let three = [[1u8; 32], [2u8; 32], [3u8; 32]];
+        assert_ne!(poseidon_hash_many(&two), poseidon_hash_many(&three));
+    }
+}
@@ -4,7 +4,7 @@ use test_utils::{
     test_helpers::{
         create_test_config, expect_proof_failure, generate_and_verify_proof, TestScenario,
     },
-    TestConfig,
+    ProofMode, RetryPolicy, TestConfig,
 };
 
 #[test]
@@ -209,6 +209,9 @@ fn test_proof_generation_memory_usage() {
         enable_logging: true,
         proof_timeout_secs: 300,
         max_memory_mb: 1024, // Limit memory for testing
+        proof_mode: ProofMode::Composite,
+        retry_policy: RetryPolicy::default(),
+        backend: Default::default(),
     };
 
     let result = generate_and_verify_proof(&input, &config);
@@ -1,7 +1,7 @@
 use std::time::Duration;
 use test_utils::{
     payment_instruction_generator::PaymentInstructionGenerator,
-    test_helpers::{create_test_config, generate_and_verify_proof, TestScenario},
+    test_helpers::{create_test_config, generate_and_verify_proof, GasWeights, TestScenario},
 };
 
 /// Phase 3: Integration Testing - Complete E2E Pipeline Tests
@@ -60,12 +60,9 @@ fn phase3_gas_profiling() {
             println!("Proof size: {} bytes", metrics.proof_size_bytes);
             println!("Journal size: {} bytes", metrics.journal_size_bytes);
             println!("Memory usage: {} MB", metrics.memory_usage_mb);
+            println!("Verification gas: {}", metrics.verification_gas);
 
             // Validate size efficiency
-            assert!(
-                metrics.proof_size_bytes < 10000,
-                "Proof size should be under 10KB"
-            );
             assert!(
                 metrics.journal_size_bytes < 1000,
                 "Journal size should be under 1KB"
@@ -74,6 +71,16 @@ fn phase3_gas_profiling() {
                 metrics.memory_usage_mb < 1000,
                 "Memory usage should be under 1GB"
             );
+
+            // A deterministic gas threshold derived from `GasWeights`
+            // (base weight + per-byte calldata pricing + one nullifier
+            // SSTORE) replaces the old `proof_size_bytes < 10000` heuristic,
+            // which said nothing about actual on-chain verification cost.
+            assert!(
+                metrics.verification_gas < 300_000,
+                "transferWithProof gas estimate should stay under 300k: got {}",
+                metrics.verification_gas
+            );
         }
         Err(e) => {
             panic!("Gas profiling test failed: {}", e);
@@ -375,7 +382,13 @@ fn phase3_iso20022_compliance() {
     let currencies = vec!["USD", "EUR", "SGD"];
     let config = create_test_config(TestScenario::Fast);
 
-    for currency in currencies {
+    // Every currency's input/output pair gets dumped as a JSON conformance
+    // fixture, turning this run into a replayable, versioned corpus that
+    // independent verifier implementations can check against without
+    // re-running the prover.
+    let fixture_dir = tempfile::tempdir().expect("tempdir creation should succeed");
+
+    for currency in &currencies {
         println!("Testing {} format...", currency);
 
         let input_result = generator.generate_from_samples(currency);
@@ -384,16 +397,24 @@ fn phase3_iso20022_compliance() {
                 // Validate structure
                 assert!(!input.debtor_data.is_empty(), "Debtor data missing");
                 assert!(!input.creditor_data.is_empty(), "Creditor data missing");
-                assert_eq!(input.currency, currency, "Currency mismatch");
+                assert_eq!(&input.currency, currency, "Currency mismatch");
 
                 // Generate proof to validate format
                 let result = generate_and_verify_proof(&input, &config);
                 match result {
-                    Ok((_, metrics)) => {
+                    Ok((output, metrics)) => {
                         println!("  ✅ {} format validated", currency);
                         println!("     Amount: {} {}", input.amount_value, input.currency);
                         println!("     Execution Date: {}", input.execution_date);
                         println!("     Proof time: {:?}", metrics.proof_generation_time);
+
+                        generator
+                            .dump_fixture(
+                                &input,
+                                test_utils::fixtures::FixtureExpectation::Valid { output },
+                                &fixture_dir.path().join(format!("{}.json", currency)),
+                            )
+                            .expect("dump_fixture should succeed");
                     }
                     Err(e) => {
                         panic!("{} proof generation failed: {}", currency, e);
@@ -406,11 +427,24 @@ fn phase3_iso20022_compliance() {
         }
     }
 
+    let fixture_results = test_utils::fixtures::run_fixture_suite(fixture_dir.path(), &config)
+        .expect("run_fixture_suite should succeed");
+    assert_eq!(fixture_results.len(), currencies.len());
+    for result in &fixture_results {
+        assert!(
+            result.passed,
+            "fixture {} failed replay: {}",
+            result.path.display(),
+            result.detail
+        );
+    }
+
     println!("\n📊 ISO 20022 Compliance Summary");
     println!("===============================");
     println!("✅ All currency formats validated");
     println!("✅ Debtor/Creditor structure correct");
     println!("✅ ISO 20022 payment instruction compliance verified");
+    println!("✅ {} fixtures replayed successfully", fixture_results.len());
 }
 
 #[test]
@@ -480,6 +514,21 @@ fn phase3_performance_thresholds() {
     assert!(*thresholds.get("max_verification_millis").unwrap() <= 5000);
     assert!(*thresholds.get("max_memory_mb").unwrap() <= 2000);
 
+    // Unlike the thresholds above (bounds the prover's own timing/memory
+    // can drift within), `GasWeights` gives an exact, reproducible gas
+    // figure for a known-size calldata payload, independent of which
+    // machine ran the proof.
+    let weights = GasWeights::calibrated();
+    let journal_bytes = 153; // ENCODED_PAYLOAD_LEN: 4 hashes + 3 u64s + 1 suite byte.
+    let seal_bytes = 256; // A Groth16 proof's flat (A, B, C) encoding: 8 uint256 words.
+    let sample_calldata = vec![0xABu8; journal_bytes + seal_bytes];
+    let expected_gas = weights.estimate_gas(&sample_calldata, 1);
+    assert_eq!(
+        expected_gas,
+        weights.base_weight + (journal_bytes + seal_bytes) as u64 * weights.gas_per_nonzero_byte + weights.gas_per_sstore,
+        "gas estimate must be exactly reproducible from the weight model"
+    );
+
     println!("✅ Performance thresholds validated");
 }
 
@@ -508,6 +557,19 @@ fn phase3_run_all() {
     println!("\n4. ISO 20022 Compliance:");
     phase3_iso20022_compliance();
 
+    // Test 5: Cross-backend agreement — every registered `ProverBackend`
+    // that can actually run here must commit the same root/debtor_hash/
+    // creditor_hash, so a future second real backend (SP1, a different
+    // zkVM) alongside RISC Zero is caught diverging immediately.
+    println!("\n5. Cross-Backend Agreement:");
+    let mut generator = PaymentInstructionGenerator::new();
+    let input = generator.generate_valid_input();
+    let config = create_test_config(TestScenario::Fast);
+    let backends = test_utils::prover_backend::ProverBackendFactory::all();
+    test_utils::prover_backend::assert_backends_agree_on_output(&backends, &input, &config)
+        .expect("registered backends should agree on committed output");
+    println!("✅ Backends agree on committed output");
+
     println!("\n🎉 Phase 3 Test Suite Completed Successfully!");
     println!("============================================");
     println!("All core integration tests passed.");